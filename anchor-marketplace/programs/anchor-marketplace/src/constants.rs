@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+#[constant]
+pub const REWARDS_DECIMALS: u8 = 6;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Upper bound on the marketplace fee, 10%.
+#[constant]
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Bids this close to an auction's end push the end back to this far out.
+#[constant]
+pub const ANTI_SNIPING_WINDOW: i64 = 10 * 60;
+
+/// Metaplex allows at most this many creators per NFT.
+pub const MAX_CREATORS: usize = 5;
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_interface::{TokenAccount, Mint, TokenInterface, TransferChecked, transfer_checked},
+    metadata::{Metadata, MetadataAccount, MasterEditionAccount},
+    associated_token::AssociatedToken
+};
+
+use crate::errors::MarketplaceError;
+use crate::state::{collection_bid::CollectionBid, marketplace::Marketplace, royalty::Royalty};
+use crate::transfer::{pay_royalties, transfer_lamports};
+
+#[derive(Accounts)]
+pub struct AcceptCollectionBid<'info> {
+    #[account(mut)]
+    maker: Signer<'info>,
+    #[account(mut)]
+    bidder: SystemAccount<'info>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    maker_mint: Box<InterfaceAccount<'info, Mint>>,
+    collection_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::authority = maker,
+        associated_token::mint = maker_mint,
+    )]
+    maker_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = bidder,
+    )]
+    bidder_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        has_one = bidder,
+        constraint = bid.collection == collection_mint.key() @ MarketplaceError::CollectionMismatch,
+        seeds = [
+            b"bid",
+            marketplace.key().as_ref(),
+            collection_mint.key().as_ref(),
+            bidder.key().as_ref()
+        ],
+        bump = bid.bump,
+    )]
+    bid: Box<Account<'info, CollectionBid>>,
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+        constraint = metadata.collection.as_ref().is_some_and(|c| c.verified && c.key == collection_mint.key())
+            @ MarketplaceError::CollectionMismatch,
+    )]
+    metadata: Box<Account<'info, MetadataAccount>>,
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            maker_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    master_edition: Box<Account<'info, MasterEditionAccount>>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump = marketplace.treasury_bump,
+    )]
+    treasury: SystemAccount<'info>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+    token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> AcceptCollectionBid<'info> {
+    pub fn send_nft(&mut self) -> Result<()> {
+        let accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            to: self.bidder_ata.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+
+    /// Pays for one NFT and closes the bid back to the bidder once it is
+    /// fully filled. The NFT is not listed, so royalties come straight from
+    /// its metadata; `creators` are the verified creators' wallets in
+    /// metadata order.
+    pub fn send_sol(&mut self, creators: &[AccountInfo<'info>]) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.bid.expires_at,
+            MarketplaceError::OfferExpired
+        );
+
+        let amount = self.bid.amount;
+        let bid = self.bid.to_account_info();
+
+        let royalty = Royalty::from_metadata(&self.metadata);
+        let available = self.marketplace.after_fee(amount)?;
+        let payouts = royalty.payouts(amount, available, creators)?;
+        let royalties = pay_royalties(&bid, &payouts)?;
+        let (fee, proceeds) = self.marketplace.split(amount, royalties)?;
+
+        transfer_lamports(&bid, &self.maker.to_account_info(), proceeds)?;
+        transfer_lamports(&bid, &self.treasury.to_account_info(), fee)?;
+
+        self.bid.quantity -= 1;
+        if self.bid.quantity == 0 {
+            self.bid.close(self.bidder.to_account_info())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account, transfer_checked, TransferChecked}, associated_token::AssociatedToken};
+
+use crate::errors::MarketplaceError;
+use crate::state::{listing::Listing, marketplace::Marketplace, offer::Offer};
+use crate::transfer::{pay_royalties, transfer_lamports};
+
+/// The NFT's collection was verified when it was listed, so accepting an
+/// offer only has to release the vault.
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(mut)]
+    maker: Signer<'info>,
+    #[account(mut)]
+    bidder: SystemAccount<'info>,
+    maker_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        constraint = !listing.has_bids() @ MarketplaceError::AuctionHasBids,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    listing: Box<Account<'info, Listing>>,
+    #[account(
+        mut,
+        associated_token::authority = listing,
+        associated_token::mint = maker_mint,
+    )]
+    vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        has_one = listing,
+        seeds = [b"offer", listing.key().as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+    )]
+    offer: Box<Account<'info, Offer>>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = maker_mint,
+        associated_token::authority = bidder,
+    )]
+    bidder_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump = marketplace.treasury_bump,
+    )]
+    treasury: SystemAccount<'info>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+    token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> AcceptOffer<'info> {
+    /// `creators` are the royalty creators' wallets, in the order of
+    /// `listing.royalty`.
+    pub fn send_sol(&mut self, creators: &[AccountInfo<'info>]) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < self.offer.expires_at,
+            MarketplaceError::OfferExpired
+        );
+
+        let amount = self.offer.amount;
+        let offer = self.offer.to_account_info();
+
+        let available = self.marketplace.after_fee(amount)?;
+        let payouts = self.listing.royalty.payouts(amount, available, creators)?;
+        let royalties = pay_royalties(&offer, &payouts)?;
+        let (fee, proceeds) = self.marketplace.split(amount, royalties)?;
+
+        transfer_lamports(&offer, &self.maker.to_account_info(), proceeds)?;
+        transfer_lamports(&offer, &self.treasury.to_account_info(), fee)
+    }
+
+    pub fn send_nft(&mut self) -> Result<()> {
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..],
+            &self.maker_mint.key().to_bytes()[..],
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.bidder_ata.to_account_info(),
+            authority: self.listing.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+
+    pub fn close_mint_vault(&mut self) -> Result<()> {
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..],
+            &self.maker_mint.key().to_bytes()[..],
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.listing.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds
+        );
+
+        close_account(cpi_ctx)
+    }
+}
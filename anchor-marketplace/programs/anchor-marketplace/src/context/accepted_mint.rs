@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::MarketplaceError;
+use crate::state::{accepted_mint::AcceptedMint, marketplace::Marketplace};
+
+#[derive(Accounts)]
+pub struct AddAcceptedMint<'info> {
+    #[account(mut)]
+    admin: Signer<'info>,
+    #[account(
+        has_one = admin @ MarketplaceError::Unauthorized,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    payment_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + AcceptedMint::INIT_SPACE,
+        seeds = [b"accepted_mint", marketplace.key().as_ref(), payment_mint.key().as_ref()],
+        bump,
+    )]
+    accepted_mint: Box<Account<'info, AcceptedMint>>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> AddAcceptedMint<'info> {
+    pub fn add_accepted_mint(&mut self, bumps: &AddAcceptedMintBumps) -> Result<()> {
+        self.accepted_mint.set_inner(AcceptedMint {
+            marketplace: self.marketplace.key(),
+            mint: self.payment_mint.key(),
+            bump: bumps.accepted_mint,
+        });
+
+        Ok(())
+    }
+}
+
+/// Stops new listings and purchases in the mint. Makers of existing listings
+/// can still delist.
+#[derive(Accounts)]
+pub struct RemoveAcceptedMint<'info> {
+    #[account(mut)]
+    admin: Signer<'info>,
+    #[account(
+        has_one = admin @ MarketplaceError::Unauthorized,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        mut,
+        close = admin,
+        has_one = marketplace,
+        seeds = [b"accepted_mint", marketplace.key().as_ref(), accepted_mint.mint.as_ref()],
+        bump = accepted_mint.bump,
+    )]
+    accepted_mint: Box<Account<'info, AcceptedMint>>,
+}
@@ -0,0 +1,189 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::MarketplaceError;
+use crate::state::marketplace::Marketplace;
+
+#[derive(Accounts)]
+pub struct UpdateMarketplace<'info> {
+    admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ MarketplaceError::Unauthorized,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+}
+
+impl<'info> UpdateMarketplace<'info> {
+    pub fn set_reward_rates(
+        &mut self,
+        taker_reward_rate: u64,
+        maker_reward_rate: u64,
+        redeem_rate: u64,
+    ) -> Result<()> {
+        self.marketplace.taker_reward_rate = taker_reward_rate;
+        self.marketplace.maker_reward_rate = maker_reward_rate;
+        self.marketplace.redeem_rate = redeem_rate;
+
+        Ok(())
+    }
+
+    pub fn update_fee(&mut self, fee: u16) -> Result<()> {
+        Marketplace::validate_fee(fee)?;
+        self.marketplace.fee = fee;
+
+        Ok(())
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.marketplace.paused = paused;
+
+        Ok(())
+    }
+
+    /// First half of an admin handover; `new_admin` must accept before it
+    /// takes effect. Proposing again replaces the pending admin.
+    pub fn propose_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        self.marketplace.pending_admin = Some(new_admin);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    new_admin: Signer<'info>,
+    #[account(
+        mut,
+        constraint = marketplace.pending_admin == Some(new_admin.key()) @ MarketplaceError::NotPendingAdmin,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+}
+
+impl<'info> AcceptAdmin<'info> {
+    pub fn accept_admin(&mut self) -> Result<()> {
+        self.marketplace.admin = self.new_admin.key();
+        self.marketplace.pending_admin = None;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    admin: Signer<'info>,
+    #[account(
+        has_one = admin @ MarketplaceError::Unauthorized,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump = marketplace.treasury_bump,
+    )]
+    treasury: SystemAccount<'info>,
+    #[account(mut)]
+    destination: SystemAccount<'info>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawTreasury<'info> {
+    pub fn withdraw_treasury(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, MarketplaceError::InvalidAmount);
+
+        // The treasury keeps its rent-exempt minimum so it stays usable.
+        let available = self
+            .treasury
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        require!(amount <= available, MarketplaceError::InsufficientTreasury);
+
+        let marketplace = self.marketplace.key();
+        let seeds = &[
+            b"treasury",
+            marketplace.as_ref(),
+            &[self.marketplace.treasury_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = Transfer {
+            from: self.treasury.to_account_info(),
+            to: self.destination.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryTokens<'info> {
+    admin: Signer<'info>,
+    #[account(
+        has_one = admin @ MarketplaceError::Unauthorized,
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump = marketplace.treasury_bump,
+    )]
+    treasury: SystemAccount<'info>,
+    payment_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    treasury_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::token_program = token_program,
+    )]
+    destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawTreasuryTokens<'info> {
+    pub fn withdraw_treasury_tokens(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, MarketplaceError::InvalidAmount);
+        require!(amount <= self.treasury_ata.amount, MarketplaceError::InsufficientTreasury);
+
+        let marketplace = self.marketplace.key();
+        let seeds = &[
+            b"treasury",
+            marketplace.as_ref(),
+            &[self.marketplace.treasury_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: self.treasury_ata.to_account_info(),
+            to: self.destination.to_account_info(),
+            authority: self.treasury.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, amount, self.payment_mint.decimals)
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+use crate::state::collection_bid::CollectionBid;
+
+/// Closing the bid returns the unfilled amount and its rent together.
+#[derive(Accounts)]
+pub struct CancelCollectionBid<'info> {
+    #[account(mut)]
+    bidder: Signer<'info>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [
+            b"bid",
+            bid.marketplace.as_ref(),
+            bid.collection.as_ref(),
+            bidder.key().as_ref()
+        ],
+        bump = bid.bump,
+    )]
+    bid: Box<Account<'info, CollectionBid>>,
+}
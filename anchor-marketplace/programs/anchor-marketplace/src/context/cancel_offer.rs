@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+use crate::state::offer::Offer;
+
+/// Closing the offer returns the escrowed amount and its rent together.
+/// Works whether or not the listing still exists.
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut)]
+    bidder: Signer<'info>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [b"offer", offer.listing.as_ref(), bidder.key().as_ref()],
+        bump = offer.bump,
+    )]
+    offer: Box<Account<'info, Offer>>,
+}
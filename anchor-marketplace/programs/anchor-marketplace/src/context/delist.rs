@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked};
+use crate::errors::MarketplaceError;
 use crate::state::{listing::Listing, marketplace::Marketplace};
 
 #[derive(Accounts)]
@@ -7,7 +8,7 @@ pub struct Delist<'info> {
     #[account(mut)]
     maker: Signer<'info>,
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()],
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
         bump = marketplace.bump,
     )]
     marketplace: Box<Account<'info, Marketplace>>,
@@ -21,6 +22,8 @@ pub struct Delist<'info> {
     #[account(
         mut,
         close = maker,
+        has_one = maker,
+        constraint = !listing.has_bids() @ MarketplaceError::AuctionHasBids,
         seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
         bump = listing.bump,
     )]
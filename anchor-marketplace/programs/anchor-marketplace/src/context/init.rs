@@ -3,6 +3,7 @@ use anchor_spl::token_interface::{Mint, TokenInterface};
 
 use crate::state::marketplace::Marketplace;
 use crate::errors::MarketplaceError;
+use crate::constants::REWARDS_DECIMALS;
 
 #[derive(Accounts)]
 #[instruction(name: String)]
@@ -11,9 +12,9 @@ pub struct Initialize<'info> {
     admin: Signer<'info>,
     #[account(
         init,
-        space = Marketplace::INIT_SPACE,
+        space = 8 + Marketplace::INIT_SPACE,
         payer = admin,
-        seeds = [b"marketplace", name.as_str().as_bytes()],
+        seeds = [b"marketplace", name.as_bytes()],
         bump
     )]
     marketplace: Box<Account<'info, Marketplace>>,
@@ -22,7 +23,7 @@ pub struct Initialize<'info> {
         seeds = [b"rewards", marketplace.key().as_ref()],
         bump,
         payer = admin,
-        mint::decimals = 6,
+        mint::decimals = REWARDS_DECIMALS,
         mint::authority = marketplace,
     )]
     rewards_mint: Box<InterfaceAccount<'info, Mint>>,
@@ -38,7 +39,9 @@ pub struct Initialize<'info> {
 impl<'info> Initialize<'info> {
     pub fn init(&mut self, name: String, fee: u16, bumps: &InitializeBumps) -> Result<()> {
     
-        require!(name.len() > 0 && name.len() < 33, MarketplaceError::NameTooLong);
+        require!(!name.is_empty() && name.len() < 33, MarketplaceError::NameTooLong);
+        Marketplace::validate_fee(fee)?;
+
         self.marketplace.set_inner(Marketplace {
             admin: self.admin.key(),
             fee,
@@ -46,6 +49,11 @@ impl<'info> Initialize<'info> {
             bump: bumps.marketplace,
             treasury_bump: bumps.treasury,
             rewards_bump: bumps.rewards_mint,
+            taker_reward_rate: 0,
+            maker_reward_rate: 0,
+            redeem_rate: 0,
+            pending_admin: None,
+            paused: false,
         });
 
         Ok(())
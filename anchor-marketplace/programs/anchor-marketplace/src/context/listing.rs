@@ -5,15 +5,17 @@ use anchor_spl::{
     associated_token::AssociatedToken
 };
 
-use crate::state::{listing::Listing, marketplace::Marketplace};
+use crate::errors::MarketplaceError;
+use crate::state::{accepted_mint::AcceptedMint, royalty::Royalty, listing::{DecayCurve, DutchAuction, EnglishAuction, Listing, ListingKind}, marketplace::Marketplace};
 
 #[derive(Accounts)]
 pub struct List<'info> {
     #[account(mut)]
     maker: Signer<'info>,
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()],
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
         bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
     )]
     marketplace: Box<Account<'info, Marketplace>>,
     maker_mint: Box<InterfaceAccount<'info, Mint>>,
@@ -34,7 +36,7 @@ pub struct List<'info> {
     #[account(
         init,
         payer = maker,
-        space = Listing::INIT_SPACE,
+        space = 8 + Listing::INIT_SPACE,
         seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
         bump
     )]
@@ -48,7 +50,7 @@ pub struct List<'info> {
         seeds::program = metadata_program.key(),
         bump,
         constraint = metadata.collection.as_ref().unwrap().key.as_ref() == collection_mint.key().as_ref(),
-        constraint = metadata.collection.as_ref().unwrap().verified == true,
+        constraint = metadata.collection.as_ref().unwrap().verified,
     )]
     metadata: Box<Account<'info, MetadataAccount>>,
     #[account(
@@ -62,6 +64,14 @@ pub struct List<'info> {
         bump,
     )]
     master_edition: Box<Account<'info, MasterEditionAccount>>,
+    /// Set, together with its `accepted_mint` entry, to price the listing in
+    /// an SPL token instead of lamports.
+    payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        seeds = [b"accepted_mint", marketplace.key().as_ref(), accepted_mint.mint.as_ref()],
+        bump = accepted_mint.bump,
+    )]
+    accepted_mint: Option<Box<Account<'info, AcceptedMint>>>,
     metadata_program: Program<'info, Metadata>,
     associated_token_program: Program<'info, AssociatedToken>,
     system_program: Program<'info, System>,
@@ -69,12 +79,97 @@ pub struct List<'info> {
 }
 
 impl<'info> List<'info> {
+    fn royalty(&self) -> Result<Royalty> {
+        let royalty = Royalty::from_metadata(&self.metadata);
+        self.marketplace.validate_royalty(&royalty)?;
+        Ok(royalty)
+    }
+
+    fn checked_payment_mint(&self) -> Result<Option<Pubkey>> {
+        match (&self.payment_mint, &self.accepted_mint) {
+            (None, _) => Ok(None),
+            (Some(mint), Some(accepted)) if accepted.mint == mint.key() => Ok(Some(mint.key())),
+            _ => err!(MarketplaceError::PaymentMintNotAllowed),
+        }
+    }
+
     pub fn create_listing(&mut self, price: u64, bumps: &ListBumps) -> Result<()> {
+        let payment_mint = self.checked_payment_mint()?;
+
         self.listing.set_inner(Listing {
             maker: self.maker.key(),
             mint: self.maker_mint.key(),
             price,
             bump: bumps.listing,
+            kind: ListingKind::FixedPrice,
+            payment_mint,
+            royalty: self.royalty()?,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_auction(
+        &mut self,
+        reserve: u64,
+        start: i64,
+        end: i64,
+        min_increment_bps: u16,
+        bumps: &ListBumps,
+    ) -> Result<()> {
+        require!(
+            start < end && end > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidAuction
+        );
+        // Bids are escrowed as lamports in the listing.
+        require!(self.payment_mint.is_none(), MarketplaceError::SolOnlyAuction);
+
+        self.listing.set_inner(Listing {
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            price: reserve,
+            bump: bumps.listing,
+            kind: ListingKind::English(EnglishAuction {
+                start,
+                end,
+                min_increment_bps,
+                highest_bid: 0,
+                highest_bidder: None,
+            }),
+            payment_mint: None,
+            royalty: self.royalty()?,
+        });
+
+        Ok(())
+    }
+
+    pub fn create_dutch_auction(
+        &mut self,
+        start_price: u64,
+        floor_price: u64,
+        start: i64,
+        end: i64,
+        curve: DecayCurve,
+        bumps: &ListBumps,
+    ) -> Result<()> {
+        let auction = DutchAuction {
+            start_price,
+            floor_price,
+            start,
+            end,
+            curve,
+        };
+        auction.validate()?;
+        let payment_mint = self.checked_payment_mint()?;
+
+        self.listing.set_inner(Listing {
+            maker: self.maker.key(),
+            mint: self.maker_mint.key(),
+            price: start_price,
+            bump: bumps.listing,
+            kind: ListingKind::Dutch(auction),
+            payment_mint,
+            royalty: self.royalty()?,
         });
 
         Ok(())
@@ -0,0 +1,68 @@
+use anchor_lang::{prelude::*, system_program::{Transfer, transfer}};
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::MarketplaceError;
+use crate::state::{collection_bid::CollectionBid, marketplace::Marketplace};
+
+#[derive(Accounts)]
+pub struct MakeCollectionBid<'info> {
+    #[account(mut)]
+    bidder: Signer<'info>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    collection_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + CollectionBid::INIT_SPACE,
+        seeds = [
+            b"bid",
+            marketplace.key().as_ref(),
+            collection_mint.key().as_ref(),
+            bidder.key().as_ref()
+        ],
+        bump,
+    )]
+    bid: Box<Account<'info, CollectionBid>>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MakeCollectionBid<'info> {
+    pub fn make_collection_bid(
+        &mut self,
+        amount: u64,
+        quantity: u64,
+        expires_at: i64,
+        bumps: &MakeCollectionBidBumps,
+    ) -> Result<()> {
+        require!(amount > 0 && quantity > 0, MarketplaceError::InvalidAmount);
+        require!(expires_at > Clock::get()?.unix_timestamp, MarketplaceError::InvalidExpiry);
+
+        let total = amount
+            .checked_mul(quantity)
+            .ok_or(MarketplaceError::Overflow)?;
+
+        self.bid.set_inner(CollectionBid {
+            bidder: self.bidder.key(),
+            marketplace: self.marketplace.key(),
+            collection: self.collection_mint.key(),
+            amount,
+            quantity,
+            expires_at,
+            bump: bumps.bid,
+        });
+
+        let accounts = Transfer {
+            from: self.bidder.to_account_info(),
+            to: self.bid.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+        transfer(cpi_ctx, total)
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::{prelude::*, system_program::{Transfer, transfer}};
+
+use crate::errors::MarketplaceError;
+use crate::state::{listing::Listing, marketplace::Marketplace, offer::Offer};
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    bidder: Signer<'info>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        seeds = [marketplace.key().as_ref(), listing.mint.as_ref()],
+        bump = listing.bump,
+    )]
+    listing: Box<Account<'info, Listing>>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"offer", listing.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    offer: Box<Account<'info, Offer>>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> MakeOffer<'info> {
+    pub fn make_offer(&mut self, amount: u64, expires_at: i64, bumps: &MakeOfferBumps) -> Result<()> {
+        require!(amount > 0, MarketplaceError::InvalidAmount);
+        require!(expires_at > Clock::get()?.unix_timestamp, MarketplaceError::InvalidExpiry);
+
+        self.offer.set_inner(Offer {
+            bidder: self.bidder.key(),
+            listing: self.listing.key(),
+            amount,
+            expires_at,
+            bump: bumps.offer,
+        });
+
+        let accounts = Transfer {
+            from: self.bidder.to_account_info(),
+            to: self.offer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+        transfer(cpi_ctx, amount)
+    }
+}
@@ -2,8 +2,30 @@ pub mod init;
 pub mod listing;
 pub mod delist;
 pub mod purchase;
+pub mod admin;
+pub mod redeem;
+pub mod make_offer;
+pub mod accept_offer;
+pub mod cancel_offer;
+pub mod make_collection_bid;
+pub mod accept_collection_bid;
+pub mod cancel_collection_bid;
+pub mod place_bid;
+pub mod settle_auction;
+pub mod accepted_mint;
 
 pub use init::*;
 pub use listing::*;
 pub use delist::*;
-pub use purchase::*;
\ No newline at end of file
+pub use purchase::*;
+pub use admin::*;
+pub use redeem::*;
+pub use make_offer::*;
+pub use accept_offer::*;
+pub use cancel_offer::*;
+pub use make_collection_bid::*;
+pub use accept_collection_bid::*;
+pub use cancel_collection_bid::*;
+pub use place_bid::*;
+pub use settle_auction::*;
+pub use accepted_mint::*;
\ No newline at end of file
@@ -0,0 +1,71 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::errors::MarketplaceError;
+use crate::state::{
+    listing::{Listing, ListingKind},
+    marketplace::Marketplace,
+};
+use crate::transfer::transfer_lamports;
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    bidder: Signer<'info>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        mut,
+        seeds = [marketplace.key().as_ref(), listing.mint.as_ref()],
+        bump = listing.bump,
+    )]
+    listing: Box<Account<'info, Listing>>,
+    /// The current high bidder, refunded in full. Required once the
+    /// auction has a bid.
+    #[account(mut)]
+    previous_bidder: Option<SystemAccount<'info>>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceBid<'info> {
+    pub fn place_bid(&mut self, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let reserve = self.listing.price;
+
+        let ListingKind::English(auction) = &mut self.listing.kind else {
+            return err!(MarketplaceError::NotAuction);
+        };
+        let outbid = auction.bid(self.bidder.key(), amount, reserve, now)?;
+
+        let accounts = Transfer {
+            from: self.bidder.to_account_info(),
+            to: self.listing.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+        transfer(cpi_ctx, amount)?;
+
+        if let Some((previous, refund)) = outbid {
+            let previous_bidder = self
+                .previous_bidder
+                .as_ref()
+                .filter(|account| account.key() == previous)
+                .ok_or(MarketplaceError::InvalidPreviousBidder)?;
+
+            transfer_lamports(
+                &self.listing.to_account_info(),
+                &previous_bidder.to_account_info(),
+                refund,
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,18 +1,24 @@
 use anchor_lang::{prelude::*, system_program::{Transfer, transfer}};
-use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account, transfer_checked, TransferChecked}, associated_token::AssociatedToken};
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account, transfer_checked, TransferChecked, MintTo, mint_to}, associated_token::AssociatedToken};
 
-use crate::state::{marketplace::Marketplace, listing::Listing};
+use crate::constants::REWARDS_DECIMALS;
+use crate::errors::MarketplaceError;
+use crate::state::{accepted_mint::AcceptedMint, marketplace::Marketplace, listing::Listing, credit::FeeCredit, royalty::total_paid};
 
 #[derive(Accounts)]
 pub struct Purchase<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = taker.key() != maker.key() @ MarketplaceError::SelfPurchase,
+    )]
     taker: Signer<'info>,
     #[account(mut)]
     maker: SystemAccount<'info>,
     maker_mint: InterfaceAccount<'info, Mint>,
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()],
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
         bump = marketplace.bump,
+        constraint = !marketplace.paused @ MarketplaceError::MarketplacePaused,
     )]
     marketplace: Box<Account<'info, Marketplace>>,
     #[account(
@@ -32,29 +38,122 @@ pub struct Purchase<'info> {
         mut,
         seeds = [b"rewards", marketplace.key().as_ref()],
         bump = marketplace.rewards_bump,
-        mint::decimals = 6,
+        mint::decimals = REWARDS_DECIMALS,
         mint::authority = marketplace,
     )]
     rewards: Box<InterfaceAccount<'info, Mint>>,
+    /// Only needed for lamport-priced sales, which are the only ones that
+    /// earn rewards.
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = rewards,
+        associated_token::authority = taker,
+    )]
+    taker_rewards: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = rewards,
+        associated_token::authority = maker,
+    )]
+    maker_rewards: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        seeds = [b"credit", marketplace.key().as_ref(), taker.key().as_ref()],
+        bump = credit.bump,
+    )]
+    credit: Option<Box<Account<'info, FeeCredit>>>,
     #[account(
         mut,
         close = maker,
+        has_one = maker,
         seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
         bump = listing.bump,
     )]
     listing: Box<Account<'info, Listing>>,
     #[account(
+        mut,
         seeds = [b"treasury", marketplace.key().as_ref()],
         bump = marketplace.treasury_bump,
     )]
     treasury: SystemAccount<'info>,
+    /// The remaining accounts are only needed for listings priced in an SPL
+    /// token, and must match `listing.payment_mint`.
+    payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    #[account(
+        seeds = [b"accepted_mint", marketplace.key().as_ref(), accepted_mint.mint.as_ref()],
+        bump = accepted_mint.bump,
+    )]
+    accepted_mint: Option<Box<Account<'info, AcceptedMint>>>,
+    #[account(
+        mut,
+        associated_token::mint = payment_mint,
+        associated_token::authority = taker,
+        associated_token::token_program = payment_token_program,
+    )]
+    taker_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = maker,
+        associated_token::token_program = payment_token_program,
+    )]
+    maker_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = payment_token_program,
+    )]
+    treasury_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    payment_token_program: Option<Interface<'info, TokenInterface>>,
     associated_token_program: Program<'info, AssociatedToken>,
     system_program: Program<'info, System>,
     token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> Purchase<'info> {
-    pub fn send_sol(&self) -> Result<()> {
+    /// Dutch auction prices move with the clock, so the buyer caps what
+    /// they are willing to pay.
+    pub fn sale_price(&self, max_price: u64) -> Result<u64> {
+        let price = self.listing.current_price(Clock::get()?.unix_timestamp)?;
+        require!(price <= max_price, MarketplaceError::PriceAboveMax);
+
+        Ok(price)
+    }
+
+    /// `creators` are the royalty creators' wallets, or their token accounts
+    /// for token-priced listings, in the order of `listing.royalty`.
+    pub fn pay(&mut self, price: u64, creators: &[AccountInfo<'info>]) -> Result<()> {
+        match self.listing.payment_mint {
+            Some(payment_mint) => self.send_tokens(price, payment_mint, creators),
+            None => self.send_sol(price, creators),
+        }
+    }
+
+    pub fn send_sol(&mut self, price: u64, creators: &[AccountInfo<'info>]) -> Result<()> {
+        let available = self.marketplace.after_fee(price)?;
+        let payouts = self.listing.royalty.payouts(price, available, creators)?;
+        for (creator, amount) in &payouts {
+            if *amount == 0 {
+                continue;
+            }
+
+            let accounts = Transfer {
+                from: self.taker.to_account_info(),
+                to: (*creator).clone(),
+            };
+
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+
+            transfer(cpi_ctx, *amount)?;
+        }
+
+        let (fee, proceeds) = self.marketplace.split(price, total_paid(&payouts))?;
+
         let accounts = Transfer {
             from: self.taker.to_account_info(),
             to: self.maker.to_account_info(),
@@ -62,11 +161,17 @@ impl<'info> Purchase<'info> {
 
         let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
 
-        let amount = self.listing.price
-            .checked_mul(self.marketplace.fee as u64).unwrap()
-            .checked_div(10000).unwrap();
+        transfer(cpi_ctx, proceeds)?;
 
-        transfer(cpi_ctx, self.listing.price - amount)?;
+        // Redeemed rewards come off the marketplace's cut, never the maker's.
+        let discount = match self.credit.as_mut() {
+            Some(credit) => credit.apply(fee),
+            None => 0,
+        };
+
+        if fee == discount {
+            return Ok(());
+        }
 
         let accounts = Transfer {
             from: self.taker.to_account_info(),
@@ -75,7 +180,127 @@ impl<'info> Purchase<'info> {
 
         let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
 
-        transfer(cpi_ctx, amount)
+        transfer(cpi_ctx, fee - discount)
+    }
+
+    /// Fee credit is denominated in lamports, so it is not spent here.
+    pub fn send_tokens(
+        &mut self,
+        price: u64,
+        payment_mint: Pubkey,
+        creators: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let (
+            Some(mint),
+            Some(taker_ata),
+            Some(maker_ata),
+            Some(treasury_ata),
+            Some(token_program),
+        ) = (
+            &self.payment_mint,
+            &self.taker_payment_ata,
+            &self.maker_payment_ata,
+            &self.treasury_payment_ata,
+            &self.payment_token_program,
+        )
+        else {
+            return err!(MarketplaceError::InvalidPaymentAccounts);
+        };
+        require_keys_eq!(mint.key(), payment_mint, MarketplaceError::InvalidPaymentAccounts);
+        require!(
+            self.accepted_mint.as_ref().is_some_and(|accepted| accepted.mint == payment_mint),
+            MarketplaceError::PaymentMintNotAllowed
+        );
+
+        let available = self.marketplace.after_fee(price)?;
+        let payouts = self.listing.royalty.token_payouts(price, available, payment_mint, creators)?;
+        for (creator, amount) in &payouts {
+            if *amount == 0 {
+                continue;
+            }
+
+            let accounts = TransferChecked {
+                from: taker_ata.to_account_info(),
+                to: (*creator).clone(),
+                authority: self.taker.to_account_info(),
+                mint: mint.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(token_program.to_account_info(), accounts);
+
+            transfer_checked(cpi_ctx, *amount, mint.decimals)?;
+        }
+
+        let (fee, proceeds) = self.marketplace.split(price, total_paid(&payouts))?;
+
+        let accounts = TransferChecked {
+            from: taker_ata.to_account_info(),
+            to: maker_ata.to_account_info(),
+            authority: self.taker.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_ctx, proceeds, mint.decimals)?;
+
+        if fee == 0 {
+            return Ok(());
+        }
+
+        let accounts = TransferChecked {
+            from: taker_ata.to_account_info(),
+            to: treasury_ata.to_account_info(),
+            authority: self.taker.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), accounts);
+
+        transfer_checked(cpi_ctx, fee, mint.decimals)
+    }
+
+    /// Reward rates are per SOL, so only lamport-priced sales earn rewards.
+    pub fn mint_rewards(&self, price: u64) -> Result<()> {
+        if self.listing.payment_mint.is_some() {
+            return Ok(());
+        }
+
+        let taker_amount = self.marketplace.rewards_for(price, self.marketplace.taker_reward_rate)?;
+        let maker_amount = self.marketplace.rewards_for(price, self.marketplace.maker_reward_rate)?;
+
+        self.mint_reward(&self.taker_rewards, taker_amount)?;
+        self.mint_reward(&self.maker_rewards, maker_amount)
+    }
+
+    fn mint_reward(&self, to: &Option<Box<InterfaceAccount<'info, TokenAccount>>>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let Some(to) = to else {
+            return err!(MarketplaceError::InvalidRewardAccounts);
+        };
+
+        let seeds = &[
+            b"marketplace",
+            self.marketplace.name.as_bytes(),
+            &[self.marketplace.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = MintTo {
+            mint: self.rewards.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.marketplace.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        mint_to(cpi_ctx, amount)
     }
 
     pub fn send_nft(&mut self) -> Result<()> {
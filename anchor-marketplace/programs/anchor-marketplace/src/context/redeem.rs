@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::errors::MarketplaceError;
+use crate::state::{credit::FeeCredit, marketplace::Marketplace};
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(mut)]
+    user: Signer<'info>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        mut,
+        seeds = [b"rewards", marketplace.key().as_ref()],
+        bump = marketplace.rewards_bump,
+    )]
+    rewards: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = rewards,
+        associated_token::authority = user,
+    )]
+    user_rewards: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + FeeCredit::INIT_SPACE,
+        seeds = [b"credit", marketplace.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    credit: Box<Account<'info, FeeCredit>>,
+    token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Redeem<'info> {
+    pub fn redeem(&mut self, amount: u64, bumps: &RedeemBumps) -> Result<()> {
+        require!(amount > 0, MarketplaceError::InvalidAmount);
+
+        let credit = self.marketplace.credit_for(amount)?;
+        require!(credit > 0, MarketplaceError::NothingToRedeem);
+
+        let accounts = Burn {
+            mint: self.rewards.to_account_info(),
+            from: self.user_rewards.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+
+        burn(cpi_ctx, amount)?;
+
+        self.credit.owner = self.user.key();
+        self.credit.bump = bumps.credit;
+        self.credit.lamports = self
+            .credit
+            .lamports
+            .checked_add(credit)
+            .ok_or(MarketplaceError::Overflow)?;
+
+        Ok(())
+    }
+}
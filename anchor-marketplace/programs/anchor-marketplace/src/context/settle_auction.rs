@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token_interface::{Mint, TokenAccount, TokenInterface, CloseAccount, close_account, transfer_checked, TransferChecked}, associated_token::AssociatedToken};
+
+use crate::errors::MarketplaceError;
+use crate::state::{listing::{Listing, ListingKind}, marketplace::Marketplace};
+use crate::transfer::{pay_royalties, transfer_lamports};
+
+/// Anyone can settle once the auction is over. The NFT goes to the winner,
+/// or back to the maker if nobody met the reserve.
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+    #[account(mut)]
+    maker: SystemAccount<'info>,
+    recipient: SystemAccount<'info>,
+    maker_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()],
+        bump = marketplace.bump,
+    )]
+    marketplace: Box<Account<'info, Marketplace>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = maker_mint,
+        associated_token::authority = recipient,
+    )]
+    recipient_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::authority = listing,
+        associated_token::mint = maker_mint,
+    )]
+    vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [marketplace.key().as_ref(), maker_mint.key().as_ref()],
+        bump = listing.bump,
+    )]
+    listing: Box<Account<'info, Listing>>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref()],
+        bump = marketplace.treasury_bump,
+    )]
+    treasury: SystemAccount<'info>,
+    associated_token_program: Program<'info, AssociatedToken>,
+    system_program: Program<'info, System>,
+    token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SettleAuction<'info> {
+    pub fn send_nft(&mut self) -> Result<()> {
+        let ListingKind::English(auction) = self.listing.kind else {
+            return err!(MarketplaceError::NotAuction);
+        };
+        require!(
+            Clock::get()?.unix_timestamp >= auction.end,
+            MarketplaceError::AuctionNotEnded
+        );
+        require_keys_eq!(
+            self.recipient.key(),
+            auction.highest_bidder.unwrap_or(self.maker.key()),
+            MarketplaceError::InvalidRecipient
+        );
+
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..],
+            &self.maker_mint.key().to_bytes()[..],
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.recipient_ata.to_account_info(),
+            authority: self.listing.to_account_info(),
+            mint: self.maker_mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(cpi_ctx, 1, self.maker_mint.decimals)
+    }
+
+    pub fn close_mint_vault(&mut self) -> Result<()> {
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..],
+            &self.maker_mint.key().to_bytes()[..],
+            &[self.listing.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.listing.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds
+        );
+
+        close_account(cpi_ctx)
+    }
+
+    /// Splits the escrowed winning bid between the creators, the maker and
+    /// the treasury, the same way a fixed-price purchase does.
+    pub fn send_sol(&mut self, creators: &[AccountInfo<'info>]) -> Result<()> {
+        let ListingKind::English(auction) = self.listing.kind else {
+            return err!(MarketplaceError::NotAuction);
+        };
+        if auction.highest_bidder.is_none() {
+            return Ok(());
+        }
+
+        let listing = self.listing.to_account_info();
+
+        let available = self.marketplace.after_fee(auction.highest_bid)?;
+        let payouts = self.listing.royalty.payouts(auction.highest_bid, available, creators)?;
+        let royalties = pay_royalties(&listing, &payouts)?;
+        let (fee, proceeds) = self.marketplace.split(auction.highest_bid, royalties)?;
+
+        transfer_lamports(&listing, &self.maker.to_account_info(), proceeds)?;
+        transfer_lamports(&listing, &self.treasury.to_account_info(), fee)
+    }
+}
@@ -4,4 +4,62 @@ use anchor_lang::error_code;
 pub enum MarketplaceError {
     #[msg("Name must be between 1 and 32 characters")]
     NameTooLong,
-}
\ No newline at end of file
+    #[msg("Only the marketplace admin can do this")]
+    Unauthorized,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Redeeming this amount grants no fee credit")]
+    NothingToRedeem,
+    #[msg("Fee exceeds the maximum allowed")]
+    FeeTooHigh,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Marketplace is paused")]
+    MarketplacePaused,
+    #[msg("Treasury cannot cover this withdrawal")]
+    InsufficientTreasury,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("NFT is not in the bid's collection")]
+    CollectionMismatch,
+    #[msg("Listing is not a fixed-price sale")]
+    NotFixedPrice,
+    #[msg("Listing is not an auction")]
+    NotAuction,
+    #[msg("Auction must end in the future and after it starts")]
+    InvalidAuction,
+    #[msg("Auction has not started")]
+    AuctionNotStarted,
+    #[msg("Auction has ended")]
+    AuctionEnded,
+    #[msg("Auction has not ended")]
+    AuctionNotEnded,
+    #[msg("Bid is below the reserve or minimum increment")]
+    BidTooLow,
+    #[msg("Previous high bidder account is missing or wrong")]
+    InvalidPreviousBidder,
+    #[msg("Auction has bids and can only be settled")]
+    AuctionHasBids,
+    #[msg("NFT must go to the winning bidder, or the maker if there were no bids")]
+    InvalidRecipient,
+    #[msg("Current price is above the buyer's maximum")]
+    PriceAboveMax,
+    #[msg("Payment mint is not accepted by this marketplace")]
+    PaymentMintNotAllowed,
+    #[msg("Payment accounts are missing or do not match the listing")]
+    InvalidPaymentAccounts,
+    #[msg("English auctions are priced in SOL")]
+    SolOnlyAuction,
+    #[msg("Creator accounts are missing or do not match the royalty creators")]
+    InvalidCreatorAccounts,
+    #[msg("Marketplace fee and creator royalty add up to more than the price")]
+    RoyaltyTooHigh,
+    #[msg("Makers cannot buy their own listings")]
+    SelfPurchase,
+    #[msg("Reward accounts are missing for a sale that earns rewards")]
+    InvalidRewardAccounts,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
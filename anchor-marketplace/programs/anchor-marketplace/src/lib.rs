@@ -5,9 +5,13 @@ declare_id!("8MLJwZe8HY4B54xe2hPcpf7HeDdpa1qCsnYqsTfmxqf");
 pub mod state;
 pub mod context;
 pub mod errors;
+pub mod constants;
+pub mod transfer;
 
 pub use context::*;
 pub use errors::*;
+pub use constants::*;
+pub use state::DecayCurve;
 
 #[program]
 pub mod anchor_marketplace {
@@ -26,10 +30,133 @@ pub mod anchor_marketplace {
         ctx.accounts.withdraw_nft()
     }
 
-    pub fn purchase(ctx: Context<Purchase>) -> Result<()> {
-        ctx.accounts.send_sol()?;
+    pub fn purchase<'info>(
+        ctx: Context<'_, '_, '_, 'info, Purchase<'info>>,
+        max_price: u64,
+    ) -> Result<()> {
+        let price = ctx.accounts.sale_price(max_price)?;
+        ctx.accounts.pay(price, ctx.remaining_accounts)?;
+        ctx.accounts.mint_rewards(price)?;
         ctx.accounts.send_nft()?;
         ctx.accounts.close_mint_vault()
 
     }
+
+    pub fn create_auction(
+        ctx: Context<List>,
+        reserve: u64,
+        start: i64,
+        end: i64,
+        min_increment_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.create_auction(reserve, start, end, min_increment_bps, &ctx.bumps)?;
+        ctx.accounts.deposit_nft()
+    }
+
+    pub fn create_dutch_auction(
+        ctx: Context<List>,
+        start_price: u64,
+        floor_price: u64,
+        start: i64,
+        end: i64,
+        curve: DecayCurve,
+    ) -> Result<()> {
+        ctx.accounts
+            .create_dutch_auction(start_price, floor_price, start, end, curve, &ctx.bumps)?;
+        ctx.accounts.deposit_nft()
+    }
+
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        ctx.accounts.place_bid(amount)
+    }
+
+    pub fn settle_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.send_nft()?;
+        ctx.accounts.close_mint_vault()?;
+        ctx.accounts.send_sol(ctx.remaining_accounts)
+    }
+
+    pub fn make_offer(ctx: Context<MakeOffer>, amount: u64, expires_at: i64) -> Result<()> {
+        ctx.accounts.make_offer(amount, expires_at, &ctx.bumps)
+    }
+
+    pub fn accept_offer<'info>(ctx: Context<'_, '_, '_, 'info, AcceptOffer<'info>>) -> Result<()> {
+        ctx.accounts.send_nft()?;
+        ctx.accounts.close_mint_vault()?;
+        ctx.accounts.send_sol(ctx.remaining_accounts)
+    }
+
+    pub fn cancel_offer(_ctx: Context<CancelOffer>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn make_collection_bid(
+        ctx: Context<MakeCollectionBid>,
+        amount: u64,
+        quantity: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.make_collection_bid(amount, quantity, expires_at, &ctx.bumps)
+    }
+
+    pub fn accept_collection_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptCollectionBid<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.send_nft()?;
+        ctx.accounts.send_sol(ctx.remaining_accounts)
+    }
+
+    pub fn cancel_collection_bid(_ctx: Context<CancelCollectionBid>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_reward_rates(
+        ctx: Context<UpdateMarketplace>,
+        taker_reward_rate: u64,
+        maker_reward_rate: u64,
+        redeem_rate: u64,
+    ) -> Result<()> {
+        ctx.accounts.set_reward_rates(taker_reward_rate, maker_reward_rate, redeem_rate)
+    }
+
+    pub fn update_fee(ctx: Context<UpdateMarketplace>, fee: u16) -> Result<()> {
+        ctx.accounts.update_fee(fee)
+    }
+
+    pub fn set_paused(ctx: Context<UpdateMarketplace>, paused: bool) -> Result<()> {
+        ctx.accounts.set_paused(paused)
+    }
+
+    pub fn propose_admin(ctx: Context<UpdateMarketplace>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.propose_admin(new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.accept_admin()
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_treasury(amount)
+    }
+
+    pub fn withdraw_treasury_tokens(
+        ctx: Context<WithdrawTreasuryTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_treasury_tokens(amount)
+    }
+
+    pub fn add_accepted_mint(ctx: Context<AddAcceptedMint>) -> Result<()> {
+        ctx.accounts.add_accepted_mint(&ctx.bumps)
+    }
+
+    pub fn remove_accepted_mint(_ctx: Context<RemoveAcceptedMint>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn redeem(ctx: Context<Redeem>, amount: u64) -> Result<()> {
+        ctx.accounts.redeem(amount, &ctx.bumps)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+/// Marks `mint` as a currency listings on `marketplace` may be priced in.
+/// Its existence is the permission; the admin closes it to revoke.
+#[account]
+#[derive(InitSpace)]
+pub struct AcceptedMint {
+    pub marketplace: Pubkey,
+    pub mint: Pubkey,
+    pub bump: u8,
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+/// A standing bid for any NFT in a verified collection. Holds
+/// `amount * quantity` lamports on top of its rent.
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionBid {
+    pub bidder: Pubkey,
+    pub marketplace: Pubkey,
+    pub collection: Pubkey,
+    /// Price paid per NFT.
+    pub amount: u64,
+    /// NFTs still wanted.
+    pub quantity: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
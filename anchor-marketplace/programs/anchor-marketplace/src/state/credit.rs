@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Fee discount a user has bought by burning reward tokens, in lamports.
+/// Drawn down against the marketplace fee on that user's next purchases.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeCredit {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub bump: u8,
+}
+
+impl FeeCredit {
+    /// Spends as much credit as `fee` allows and returns the discount.
+    pub fn apply(&mut self, fee: u64) -> u64 {
+        let discount = fee.min(self.lamports);
+        self.lamports -= discount;
+        discount
+    }
+}
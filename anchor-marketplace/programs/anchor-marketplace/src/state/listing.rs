@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::{ANTI_SNIPING_WINDOW, BPS_DENOMINATOR};
+use crate::errors::MarketplaceError;
+use crate::state::royalty::Royalty;
+
+/// For English auctions `price` is the reserve, and the highest bid is
+/// escrowed in this account until settlement. For Dutch auctions it is the
+/// starting price; the live price comes from `current_price`.
 #[account]
 #[derive(InitSpace)]
 pub struct Listing {
@@ -7,5 +14,159 @@ pub struct Listing {
     pub mint: Pubkey,
     pub price: u64,
     pub bump: u8,
+    pub kind: ListingKind,
+    /// SPL mint the price is denominated in, or `None` for lamports.
+    pub payment_mint: Option<Pubkey>,
+    /// Royalty terms as of listing, so later metadata edits cannot change
+    /// what a buyer agreed to.
+    pub royalty: Royalty,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq)]
+pub enum ListingKind {
+    FixedPrice,
+    English(EnglishAuction),
+    Dutch(DutchAuction),
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq)]
+pub struct EnglishAuction {
+    pub start: i64,
+    pub end: i64,
+    pub min_increment_bps: u16,
+    pub highest_bid: u64,
+    pub highest_bidder: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq)]
+pub struct DutchAuction {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start: i64,
+    pub end: i64,
+    pub curve: DecayCurve,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq)]
+pub enum DecayCurve {
+    /// Falls in a straight line from the start price to the floor at `end`.
+    Linear,
+    /// Halves the distance to the floor every `half_life` seconds, moving
+    /// linearly within each half-life, and drops to the floor at `end`.
+    Exponential { half_life: i64 },
+}
+
+impl Listing {
+    /// What a buyer pays at `now`. English auctions are never bought
+    /// outright.
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        match self.kind {
+            ListingKind::FixedPrice => Ok(self.price),
+            ListingKind::English(_) => err!(MarketplaceError::NotFixedPrice),
+            ListingKind::Dutch(auction) => auction.price_at(now),
+        }
+    }
+
+    /// An auction with a live bid holds the bidder's lamports, so the NFT
+    /// can only leave through settlement.
+    pub fn has_bids(&self) -> bool {
+        matches!(
+            self.kind,
+            ListingKind::English(EnglishAuction {
+                highest_bidder: Some(_),
+                ..
+            })
+        )
+    }
+}
+
+impl EnglishAuction {
+    pub fn min_bid(&self, reserve: u64) -> Result<u64> {
+        if self.highest_bidder.is_none() {
+            return Ok(reserve);
+        }
+
+        let increment = (self.highest_bid as u128) * (self.min_increment_bps as u128)
+            / (BPS_DENOMINATOR as u128);
+        let increment = u64::try_from(increment).map_err(|_| error!(MarketplaceError::Overflow))?;
+
+        self.highest_bid
+            .checked_add(increment.max(1))
+            .ok_or(error!(MarketplaceError::Overflow))
+    }
+
+    /// Records a bid at `now` and returns the bidder it outbid, if any.
+    pub fn bid(
+        &mut self,
+        bidder: Pubkey,
+        amount: u64,
+        reserve: u64,
+        now: i64,
+    ) -> Result<Option<(Pubkey, u64)>> {
+        require!(now >= self.start, MarketplaceError::AuctionNotStarted);
+        require!(now < self.end, MarketplaceError::AuctionEnded);
+        require!(
+            amount >= self.min_bid(reserve)?,
+            MarketplaceError::BidTooLow
+        );
+
+        let outbid = self
+            .highest_bidder
+            .map(|previous| (previous, self.highest_bid));
+
+        self.highest_bid = amount;
+        self.highest_bidder = Some(bidder);
+
+        if self.end - now < ANTI_SNIPING_WINDOW {
+            self.end = now + ANTI_SNIPING_WINDOW;
+        }
+
+        Ok(outbid)
+    }
+}
+
+impl DutchAuction {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.start_price > self.floor_price
+                && self.start < self.end
+                && self.end > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidAuction
+        );
+        if let DecayCurve::Exponential { half_life } = self.curve {
+            require!(half_life > 0, MarketplaceError::InvalidAuction);
+        }
+        Ok(())
+    }
+
+    pub fn price_at(&self, now: i64) -> Result<u64> {
+        require!(now >= self.start, MarketplaceError::AuctionNotStarted);
+        if now >= self.end {
+            return Ok(self.floor_price);
+        }
+
+        let elapsed = (now - self.start) as u128;
+        let excess = (self.start_price - self.floor_price) as u128;
+
+        let remaining = match self.curve {
+            DecayCurve::Linear => {
+                let duration = (self.end - self.start) as u128;
+                excess - excess * elapsed / duration
+            }
+            DecayCurve::Exponential { half_life } => {
+                let half_life = half_life as u128;
+                let halvings = elapsed / half_life;
+                if halvings >= 64 {
+                    0
+                } else {
+                    let upper = excess >> halvings;
+                    let lower = upper >> 1;
+                    upper - (upper - lower) * (elapsed % half_life) / half_life
+                }
+            }
+        };
 
+        // `remaining` never exceeds the start/floor spread, so this fits.
+        Ok(self.floor_price + remaining as u64)
+    }
 }
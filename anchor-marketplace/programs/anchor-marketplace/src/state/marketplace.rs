@@ -1,4 +1,8 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::native_token::LAMPORTS_PER_SOL};
+
+use crate::constants::{BPS_DENOMINATOR, MAX_FEE_BPS, REWARDS_DECIMALS};
+use crate::errors::MarketplaceError;
+use crate::state::royalty::Royalty;
 
 #[account]
 #[derive(InitSpace)]
@@ -10,4 +14,70 @@ pub struct Marketplace {
     pub rewards_bump: u8,
     #[max_len(32)]
     pub name: String,
+    /// Reward base units minted to the taker per SOL of sale price.
+    pub taker_reward_rate: u64,
+    /// Reward base units minted to the maker per SOL of sale price.
+    pub maker_reward_rate: u64,
+    /// Lamports of fee credit granted per whole reward token redeemed.
+    pub redeem_rate: u64,
+    /// Set by the current admin; becomes admin once it accepts.
+    pub pending_admin: Option<Pubkey>,
+    /// Blocks new listings and purchases. Delisting always stays open.
+    pub paused: bool,
+}
+
+impl Marketplace {
+    pub fn validate_fee(fee: u16) -> Result<()> {
+        require!(fee <= MAX_FEE_BPS, MarketplaceError::FeeTooHigh);
+        Ok(())
+    }
+
+    pub fn fee_for(&self, price: u64) -> Result<u64> {
+        let fee = (price as u128) * (self.fee as u128) / (BPS_DENOMINATOR as u128);
+        u64::try_from(fee).map_err(|_| error!(MarketplaceError::Overflow))
+    }
+
+    /// What is left of `price` for royalties and the seller once the fee is
+    /// taken.
+    pub fn after_fee(&self, price: u64) -> Result<u64> {
+        Ok(price - self.fee_for(price)?)
+    }
+
+    /// Rejects royalties that would leave nothing of a sale once the current
+    /// fee is taken.
+    pub fn validate_royalty(&self, royalty: &Royalty) -> Result<()> {
+        require!(
+            self.fee as u64 + royalty.seller_fee_basis_points as u64 <= BPS_DENOMINATOR,
+            MarketplaceError::RoyaltyTooHigh
+        );
+        Ok(())
+    }
+
+    /// Splits a sale into the marketplace fee and what the seller keeps once
+    /// `royalties` are paid.
+    pub fn split(&self, price: u64, royalties: u64) -> Result<(u64, u64)> {
+        let fee = self.fee_for(price)?;
+        let proceeds = price
+            .checked_sub(fee)
+            .and_then(|rest| rest.checked_sub(royalties))
+            .ok_or(MarketplaceError::Overflow)?;
+
+        Ok((fee, proceeds))
+    }
+
+    pub fn rewards_for(&self, price: u64, rate: u64) -> Result<u64> {
+        let rewards = (price as u128)
+            .checked_mul(rate as u128)
+            .ok_or(MarketplaceError::Overflow)?
+            / (LAMPORTS_PER_SOL as u128);
+        u64::try_from(rewards).map_err(|_| error!(MarketplaceError::Overflow))
+    }
+
+    pub fn credit_for(&self, amount: u64) -> Result<u64> {
+        let credit = (amount as u128)
+            .checked_mul(self.redeem_rate as u128)
+            .ok_or(MarketplaceError::Overflow)?
+            / 10u128.pow(REWARDS_DECIMALS as u32);
+        u64::try_from(credit).map_err(|_| error!(MarketplaceError::Overflow))
+    }
 }
@@ -1,5 +1,15 @@
 pub mod marketplace;
 pub mod listing;
+pub mod credit;
+pub mod offer;
+pub mod collection_bid;
+pub mod accepted_mint;
+pub mod royalty;
 
 pub use marketplace::*;
-pub use listing::*;
\ No newline at end of file
+pub use listing::*;
+pub use credit::*;
+pub use offer::*;
+pub use collection_bid::*;
+pub use accepted_mint::*;
+pub use royalty::*;
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+/// A bid on one listing. The offered lamports sit in this account on top of
+/// its rent until the seller accepts or the bidder cancels.
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub bidder: Pubkey,
+    pub listing: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
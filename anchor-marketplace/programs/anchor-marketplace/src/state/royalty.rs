@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{metadata::MetadataAccount, token_interface::TokenAccount};
+
+use crate::constants::{BPS_DENOMINATOR, MAX_CREATORS};
+use crate::errors::MarketplaceError;
+
+/// Royalty terms copied from Metaplex metadata. Only verified creators are
+/// kept, since anyone can add an unverified creator to metadata.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq)]
+pub struct Royalty {
+    pub seller_fee_basis_points: u16,
+    #[max_len(MAX_CREATORS)]
+    pub creators: Vec<RoyaltyCreator>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq)]
+pub struct RoyaltyCreator {
+    pub address: Pubkey,
+    /// Percentage of the royalty, out of 100.
+    pub share: u8,
+}
+
+impl Royalty {
+    pub fn from_metadata(metadata: &MetadataAccount) -> Self {
+        let creators = metadata
+            .creators
+            .iter()
+            .flatten()
+            .filter(|creator| creator.verified)
+            .take(MAX_CREATORS)
+            .map(|creator| RoyaltyCreator {
+                address: creator.address,
+                share: creator.share,
+            })
+            .collect();
+
+        Self {
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators,
+        }
+    }
+
+    /// Each verified creator's cut of `price`, in creator order. Shares of
+    /// unverified creators are not paid and stay with the seller. The royalty
+    /// is capped at `available`, what the marketplace fee leaves of the price,
+    /// so a fee raised after listing can never block the sale.
+    pub fn amounts(&self, price: u64, available: u64) -> Result<Vec<u64>> {
+        let royalty = ((price as u128) * (self.seller_fee_basis_points as u128)
+            / (BPS_DENOMINATOR as u128))
+            .min(available as u128);
+
+        self.creators
+            .iter()
+            .map(|creator| {
+                u64::try_from(royalty * (creator.share as u128) / 100)
+                    .map_err(|_| error!(MarketplaceError::Overflow))
+            })
+            .collect()
+    }
+
+    /// Pairs each creator's cut with its account from `remaining_accounts`,
+    /// which must list the creators' wallets in metadata order. A cut that
+    /// would leave a wallet below rent exemption cannot be paid without
+    /// failing the sale, so it is zeroed and stays with the seller.
+    pub fn payouts<'a, 'info>(
+        &self,
+        price: u64,
+        available: u64,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Vec<(&'a AccountInfo<'info>, u64)>> {
+        require!(
+            accounts.len() >= self.creators.len(),
+            MarketplaceError::InvalidCreatorAccounts
+        );
+        let rent = Rent::get()?;
+
+        self.creators
+            .iter()
+            .zip(accounts)
+            .zip(self.amounts(price, available)?)
+            .map(|((creator, account), amount)| {
+                require_keys_eq!(
+                    account.key(),
+                    creator.address,
+                    MarketplaceError::InvalidCreatorAccounts
+                );
+                let balance = account.lamports().saturating_add(amount);
+                let amount = if rent.is_exempt(balance, account.data_len()) { amount } else { 0 };
+                Ok((account, amount))
+            })
+            .collect()
+    }
+
+    /// Like `payouts`, for token-priced sales: the accounts must be token
+    /// accounts in `mint` owned by each creator.
+    pub fn token_payouts<'a, 'info>(
+        &self,
+        price: u64,
+        available: u64,
+        mint: Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Vec<(&'a AccountInfo<'info>, u64)>> {
+        require!(
+            accounts.len() >= self.creators.len(),
+            MarketplaceError::InvalidCreatorAccounts
+        );
+
+        self.creators
+            .iter()
+            .zip(accounts)
+            .zip(self.amounts(price, available)?)
+            .map(|((creator, account), amount)| {
+                // Ownership by a token program is enforced by the transfer itself.
+                let token_account = TokenAccount::try_deserialize(&mut &account.data.borrow()[..])?;
+                require!(
+                    token_account.owner == creator.address && token_account.mint == mint,
+                    MarketplaceError::InvalidCreatorAccounts
+                );
+                Ok((account, amount))
+            })
+            .collect()
+    }
+}
+
+/// Sum of what was actually paid out, which the seller's proceeds shrink by.
+pub fn total_paid(payouts: &[(&AccountInfo, u64)]) -> u64 {
+    payouts.iter().map(|(_, amount)| amount).sum()
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::MarketplaceError;
+use crate::state::royalty::total_paid;
+
+/// Moves lamports out of an account owned by this program. The system
+/// program cannot debit accounts it does not own, so balances are adjusted
+/// directly.
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let from_lamports = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(MarketplaceError::Overflow)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(MarketplaceError::Overflow)?;
+
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+
+    Ok(())
+}
+
+/// Pays royalty `payouts` out of a program-owned escrow and returns the
+/// total paid.
+pub fn pay_royalties(from: &AccountInfo, payouts: &[(&AccountInfo, u64)]) -> Result<u64> {
+    for (creator, amount) in payouts {
+        transfer_lamports(from, creator, *amount)?;
+    }
+
+    Ok(total_paid(payouts))
+}
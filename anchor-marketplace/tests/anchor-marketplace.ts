@@ -1,16 +1,1420 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import {
+  LAMPORTS_PER_SOL,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  unpackAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
 import { AnchorMarketplace } from "../target/types/anchor_marketplace";
+import IDL from "../target/idl/anchor_marketplace.json";
+
+const { BN } = anchor;
+
+// The metadata program itself has to be present for `Program<Metadata>`;
+// run `yarn fixtures` once to dump it from mainnet into tests/fixtures.
+const METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+
+// Advance one slot so a repeated transaction gets a fresh blockhash instead
+// of being rejected as already processed.
+const nextSlot = async (context: ProgramTestContext) => {
+  const clock = await context.banksClient.getClock();
+  context.warpToSlot(clock.slot + BigInt(1));
+};
+
+// Move the bank clock to `unixTimestamp`, keeping everything else as is.
+const warpTo = async (context: ProgramTestContext, unixTimestamp: number) => {
+  await nextSlot(context);
+  const clock = await context.banksClient.getClock();
+  context.setClock(
+    new Clock(
+      clock.slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+};
+
+const now = async (context: ProgramTestContext) =>
+  Number((await context.banksClient.getClock()).unixTimestamp);
+
+const DAY = 60 * 60 * 24;
+
+const expectError = async (promise: Promise<unknown>, code: string) => {
+  let error: unknown;
+  try {
+    await promise;
+  } catch (err) {
+    error = err;
+  }
+  assert.isDefined(error, `expected ${code}`);
+  assert.include(String(error), code);
+};
+
+const ata = (mint: PublicKey, owner: PublicKey) =>
+  getAssociatedTokenAddressSync(mint, owner, true, TOKEN_PROGRAM_ID);
+
+const findMetadata = (mint: PublicKey, suffix: Buffer[] = []) =>
+  PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      ...suffix,
+    ],
+    METADATA_PROGRAM_ID
+  )[0];
+
+const u16 = (value: number) => {
+  const buf = Buffer.alloc(2);
+  buf.writeUInt16LE(value);
+  return buf;
+};
+
+const u32 = (value: number) => {
+  const buf = Buffer.alloc(4);
+  buf.writeUInt32LE(value);
+  return buf;
+};
+
+const borshString = (value: string) => {
+  const bytes = Buffer.from(value);
+  return Buffer.concat([u32(bytes.length), bytes]);
+};
+
+type Creator = { address: PublicKey; verified: boolean; share: number };
+
+// Borsh layout of a Metaplex `Metadata` account, enough for the program to
+// deserialize it and check its collection.
+const metadataData = (
+  mint: PublicKey,
+  collection: PublicKey | null,
+  verified: boolean,
+  sellerFeeBasisPoints = 0,
+  creators: Creator[] = []
+) => {
+  const creatorsData = creators.length
+    ? Buffer.concat([
+        Buffer.from([1]),
+        u32(creators.length),
+        ...creators.map((c) =>
+          Buffer.concat([
+            c.address.toBuffer(),
+            Buffer.from([c.verified ? 1 : 0, c.share]),
+          ])
+        ),
+      ])
+    : Buffer.from([0]);
+
+  return Buffer.concat([
+    Buffer.from([4]), // Key::MetadataV1
+    mint.toBuffer(), // update authority, unused here
+    mint.toBuffer(),
+    borshString("NFT"),
+    borshString("NFT"),
+    borshString(""),
+    u16(sellerFeeBasisPoints),
+    creatorsData,
+    Buffer.from([0, 1]), // primary_sale_happened, is_mutable
+    Buffer.from([0]), // edition_nonce
+    Buffer.from([1, 0]), // TokenStandard::NonFungible
+    collection
+      ? Buffer.concat([
+          Buffer.from([1, verified ? 1 : 0]),
+          collection.toBuffer(),
+        ])
+      : Buffer.from([0]),
+    Buffer.from([0, 0, 0]), // uses, collection_details, programmable_config
+  ]);
+};
+
+const masterEditionData = () =>
+  Buffer.concat([
+    Buffer.from([6]), // Key::MasterEditionV2
+    Buffer.alloc(8),
+    Buffer.from([1]),
+    Buffer.alloc(8),
+  ]);
 
 describe("anchor-marketplace", () => {
-  // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorMarketplace>;
+  let admin: PublicKey;
+  const seller = Keypair.generate();
+  const buyer = Keypair.generate();
+  let collection: PublicKey;
+  const name = "market";
+
+  let marketplace: PublicKey;
+  let treasury: PublicKey;
+  let rewards: PublicKey;
+
+  const lamports = async (account: PublicKey) =>
+    BigInt((await context.banksClient.getAccount(account))?.lamports ?? 0);
+
+  const balance = async (account: PublicKey) => {
+    const info = await context.banksClient.getAccount(account);
+    if (!info) return BigInt(0);
+    return unpackAccount(
+      account,
+      { ...info, data: Buffer.from(info.data) },
+      info.owner
+    ).amount;
+  };
+
+  const setAccount = async (address: PublicKey, data: Buffer) => {
+    const rent = await context.banksClient.getRent();
+    context.setAccount(address, {
+      lamports: Number(rent.minimumBalance(BigInt(data.length))),
+      data,
+      owner: METADATA_PROGRAM_ID,
+      executable: false,
+    });
+  };
+
+  type NftOptions = {
+    // `null` leaves the NFT outside any collection.
+    collection?: PublicKey | null;
+    verified?: boolean;
+    sellerFeeBasisPoints?: number;
+    creators?: Creator[];
+  };
+
+  // Mints a 0-decimal NFT to `owner` with hand-written metadata and master
+  // edition accounts, in `collection` unless told otherwise.
+  const createNft = async (owner = seller, options: NftOptions = {}) => {
+    const mint = Keypair.generate();
+    const rent = await context.banksClient.getRent();
+    const account = ata(mint.publicKey, owner.publicKey);
+    const tx = new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: admin,
+        newAccountPubkey: mint.publicKey,
+        space: MINT_SIZE,
+        lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMint2Instruction(mint.publicKey, 0, admin, null),
+      createAssociatedTokenAccountIdempotentInstruction(
+        admin,
+        account,
+        owner.publicKey,
+        mint.publicKey
+      ),
+      createMintToInstruction(mint.publicKey, account, admin, 1)
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+
+    await setAccount(
+      findMetadata(mint.publicKey),
+      metadataData(
+        mint.publicKey,
+        options.collection === undefined ? collection : options.collection,
+        options.verified ?? true,
+        options.sellerFeeBasisPoints,
+        options.creators
+      )
+    );
+    await setAccount(
+      findMetadata(mint.publicKey, [Buffer.from("edition")]),
+      masterEditionData()
+    );
+
+    return mint.publicKey;
+  };
+
+  const findAcceptedMint = (paymentMint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("accepted_mint"),
+        marketplace.toBuffer(),
+        paymentMint.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  // Listings are priced in lamports unless a payment mint is given.
+  const solPricing = { paymentMint: null, acceptedMint: null };
+
+  const list = (
+    mint: PublicKey,
+    price: number,
+    maker = seller,
+    paymentMint: PublicKey | null = null
+  ) =>
+    program.methods
+      .list(new BN(price))
+      .accountsPartial({
+        maker: maker.publicKey,
+        marketplace,
+        makerMint: mint,
+        collectionMint: collection,
+        paymentMint,
+        acceptedMint: paymentMint && findAcceptedMint(paymentMint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+
+  const findCredit = (user: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("credit"), marketplace.toBuffer(), user.toBuffer()],
+      program.programId
+    )[0];
+
+  const U64_MAX = new BN("18446744073709551615");
+
+  const purchase = (
+    mint: PublicKey,
+    credit: PublicKey | null = null,
+    taker = buyer,
+    maker = seller.publicKey,
+    maxPrice = U64_MAX,
+    paymentMint: PublicKey | null = null,
+    acceptedMint = paymentMint && findAcceptedMint(paymentMint)
+  ) =>
+    program.methods
+      .purchase(maxPrice)
+      .accountsPartial({
+        taker: taker.publicKey,
+        maker,
+        makerMint: mint,
+        marketplace,
+        credit,
+        takerRewards: paymentMint ? null : ata(rewards, taker.publicKey),
+        makerRewards: paymentMint ? null : ata(rewards, maker),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        paymentMint,
+        acceptedMint,
+        takerPaymentAta: paymentMint && ata(paymentMint, taker.publicKey),
+        makerPaymentAta: paymentMint && ata(paymentMint, maker),
+        treasuryPaymentAta: paymentMint && ata(paymentMint, treasury),
+        paymentTokenProgram: paymentMint && TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+
+  before(async () => {
+    context = await startAnchor(
+      ".",
+      [{ name: "mpl_token_metadata", programId: METADATA_PROGRAM_ID }],
+      []
+    );
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorMarketplace>(
+      IDL as AnchorMarketplace,
+      provider
+    );
+    admin = provider.wallet.publicKey;
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: admin,
+          toPubkey: seller.publicKey,
+          lamports: 10 * LAMPORTS_PER_SOL,
+        }),
+        SystemProgram.transfer({
+          fromPubkey: admin,
+          toPubkey: buyer.publicKey,
+          lamports: 100 * LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    marketplace = PublicKey.findProgramAddressSync(
+      [Buffer.from("marketplace"), Buffer.from(name)],
+      program.programId
+    )[0];
+    treasury = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), marketplace.toBuffer()],
+      program.programId
+    )[0];
+    rewards = PublicKey.findProgramAddressSync(
+      [Buffer.from("rewards"), marketplace.toBuffer()],
+      program.programId
+    )[0];
+
+    const collectionMint = Keypair.generate();
+    const rent = await context.banksClient.getRent();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin,
+          newAccountPubkey: collectionMint.publicKey,
+          space: MINT_SIZE,
+          lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        createInitializeMint2Instruction(
+          collectionMint.publicKey,
+          0,
+          admin,
+          null
+        )
+      ),
+      [collectionMint]
+    );
+    collection = collectionMint.publicKey;
+
+    // 2.5% fee.
+    await program.methods
+      .initialize(name, 250)
+      .accountsPartial({ admin, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+  });
+
+  describe("listing", () => {
+    it("Lists and sells an NFT with the fee split", async () => {
+      const mint = await createNft();
+      await list(mint, LAMPORTS_PER_SOL);
+
+      const sellerBefore = await lamports(seller.publicKey);
+      await purchase(mint);
+
+      assert.equal(await balance(ata(mint, buyer.publicKey)), BigInt(1));
+      assert.equal(await lamports(treasury), BigInt(LAMPORTS_PER_SOL / 40));
+      assert.isAbove(
+        Number((await lamports(seller.publicKey)) - sellerBefore),
+        (LAMPORTS_PER_SOL * 39) / 40
+      );
+    });
+
+    it("Only pays and returns the NFT to the listing's maker", async () => {
+      const mint = await createNft();
+      await list(mint, LAMPORTS_PER_SOL);
+
+      await expectError(
+        purchase(mint, null, buyer, admin),
+        "ConstraintHasOne"
+      );
+
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          createAssociatedTokenAccountIdempotentInstruction(
+            admin,
+            ata(mint, buyer.publicKey),
+            buyer.publicKey,
+            mint
+          )
+        )
+      );
+      await expectError(
+        program.methods
+          .delist()
+          .accountsPartial({
+            maker: buyer.publicKey,
+            marketplace,
+            makerMint: mint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([buyer])
+          .rpc(),
+        "ConstraintHasOne"
+      );
+
+      await purchase(mint);
+    });
+
+    it("Rejects purchases of your own listing", async () => {
+      const mint = await createNft();
+      await list(mint, LAMPORTS_PER_SOL);
+
+      await expectError(
+        purchase(mint, null, seller, seller.publicKey),
+        "SelfPurchase"
+      );
+    });
+
+    it("Rejects NFTs outside a verified collection", async () => {
+      const mint = await createNft(seller, { verified: false });
+      await expectError(list(mint, LAMPORTS_PER_SOL), "ConstraintRaw");
+    });
+  });
+
+  describe("rewards", () => {
+    before(async () => {
+      // 1 token per SOL to the buyer, 2 per SOL to the seller, and
+      // 0.01 SOL of fee credit per token redeemed.
+      await program.methods
+        .setRewardRates(
+          new BN(1_000_000),
+          new BN(2_000_000),
+          new BN(LAMPORTS_PER_SOL / 100)
+        )
+        .accountsPartial({ admin, marketplace })
+        .rpc();
+    });
+
+    it("Only lets the admin set reward rates", async () => {
+      await expectError(
+        program.methods
+          .setRewardRates(new BN(1), new BN(1), new BN(1))
+          .accountsPartial({ admin: buyer.publicKey, marketplace })
+          .signers([buyer])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+
+    it("Mints rewards to both sides in proportion to the price", async () => {
+      const mint = await createNft();
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+      await purchase(mint);
+
+      assert.equal(
+        await balance(ata(rewards, buyer.publicKey)),
+        BigInt(2_000_000)
+      );
+      assert.equal(
+        await balance(ata(rewards, seller.publicKey)),
+        BigInt(4_000_000)
+      );
+    });
+
+    it("Burns rewards for fee credit", async () => {
+      await program.methods
+        .redeem(new BN(1_000_000))
+        .accountsPartial({ user: buyer.publicKey, marketplace })
+        .signers([buyer])
+        .rpc();
+
+      assert.equal(
+        await balance(ata(rewards, buyer.publicKey)),
+        BigInt(1_000_000)
+      );
+      const credit = await program.account.feeCredit.fetch(
+        findCredit(buyer.publicKey)
+      );
+      assert.equal(credit.lamports.toNumber(), LAMPORTS_PER_SOL / 100);
+    });
+
+    it("Rejects redemptions worth no credit", async () => {
+      await expectError(
+        program.methods
+          .redeem(new BN(1))
+          .accountsPartial({ user: buyer.publicKey, marketplace })
+          .signers([buyer])
+          .rpc(),
+        "NothingToRedeem"
+      );
+    });
+
+    it("Spends credit against the fee on the next purchase", async () => {
+      const mint = await createNft();
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+
+      const treasuryBefore = await lamports(treasury);
+      await purchase(mint, findCredit(buyer.publicKey));
+
+      // 0.05 SOL fee less 0.01 SOL of credit.
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(LAMPORTS_PER_SOL / 25)
+      );
+      const credit = await program.account.feeCredit.fetch(
+        findCredit(buyer.publicKey)
+      );
+      assert.equal(credit.lamports.toNumber(), 0);
+    });
+  });
+
+  describe("admin", () => {
+    const newAdmin = Keypair.generate();
+
+    it("Caps the fee", async () => {
+      await expectError(
+        program.methods
+          .updateFee(1_001)
+          .accountsPartial({ admin, marketplace })
+          .rpc(),
+        "FeeTooHigh"
+      );
+      await program.methods
+        .updateFee(500)
+        .accountsPartial({ admin, marketplace })
+        .rpc();
+      const state = await program.account.marketplace.fetch(marketplace);
+      assert.equal(state.fee, 500);
+    });
+
+    it("Withdraws treasury fees above the rent reserve", async () => {
+      const destination = Keypair.generate().publicKey;
+      const rent = await context.banksClient.getRent();
+      const available =
+        (await lamports(treasury)) - rent.minimumBalance(BigInt(0));
+
+      await expectError(
+        program.methods
+          .withdrawTreasury(new BN((available + BigInt(1)).toString()))
+          .accountsPartial({ admin, marketplace, destination })
+          .rpc(),
+        "InsufficientTreasury"
+      );
+
+      await program.methods
+        .withdrawTreasury(new BN(available.toString()))
+        .accountsPartial({ admin, marketplace, destination })
+        .rpc();
+      assert.equal(await lamports(destination), available);
+    });
+
+    it("Only lets the admin withdraw", async () => {
+      await expectError(
+        program.methods
+          .withdrawTreasury(new BN(1))
+          .accountsPartial({
+            admin: buyer.publicKey,
+            marketplace,
+            destination: buyer.publicKey,
+          })
+          .signers([buyer])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+
+    it("Blocks listing and purchases while paused, not delisting", async () => {
+      const listed = await createNft();
+      await list(listed, LAMPORTS_PER_SOL);
+
+      await program.methods
+        .setPaused(true)
+        .accountsPartial({ admin, marketplace })
+        .rpc();
+
+      const mint = await createNft();
+      await expectError(list(mint, LAMPORTS_PER_SOL), "MarketplacePaused");
+      await expectError(purchase(listed), "MarketplacePaused");
+
+      await program.methods
+        .delist()
+        .accountsPartial({
+          maker: seller.publicKey,
+          marketplace,
+          makerMint: listed,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+      assert.equal(await balance(ata(listed, seller.publicKey)), BigInt(1));
+
+      await program.methods
+        .setPaused(false)
+        .accountsPartial({ admin, marketplace })
+        .rpc();
+    });
+
+    it("Hands over admin in two steps", async () => {
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accountsPartial({ admin, marketplace })
+        .rpc();
+
+      await expectError(
+        program.methods
+          .acceptAdmin()
+          .accountsPartial({ newAdmin: buyer.publicKey, marketplace })
+          .signers([buyer])
+          .rpc(),
+        "NotPendingAdmin"
+      );
+
+      let state = await program.account.marketplace.fetch(marketplace);
+      assert.isTrue(state.admin.equals(admin));
+
+      await program.methods
+        .acceptAdmin()
+        .accountsPartial({ newAdmin: newAdmin.publicKey, marketplace })
+        .signers([newAdmin])
+        .rpc();
+
+      state = await program.account.marketplace.fetch(marketplace);
+      assert.isTrue(state.admin.equals(newAdmin.publicKey));
+      assert.isNull(state.pendingAdmin);
+
+      // Hand it back so later tests keep using the provider wallet.
+      await program.methods
+        .proposeAdmin(admin)
+        .accountsPartial({ admin: newAdmin.publicKey, marketplace })
+        .signers([newAdmin])
+        .rpc();
+      await program.methods
+        .acceptAdmin()
+        .accountsPartial({ newAdmin: admin, marketplace })
+        .rpc();
+    });
+  });
+
+  describe("offers", () => {
+    const findListing = (mint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [marketplace.toBuffer(), mint.toBuffer()],
+        program.programId
+      )[0];
+
+    const findOffer = (listing: PublicKey, bidder: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("offer"), listing.toBuffer(), bidder.toBuffer()],
+        program.programId
+      )[0];
+
+    const makeOffer = async (mint: PublicKey, amount: number, ttl = DAY) =>
+      program.methods
+        .makeOffer(new BN(amount), new BN((await now(context)) + ttl))
+        .accountsPartial({
+          bidder: buyer.publicKey,
+          marketplace,
+          listing: findListing(mint),
+        })
+        .signers([buyer])
+        .rpc();
+
+    const acceptOffer = (mint: PublicKey) =>
+      program.methods
+        .acceptOffer()
+        .accountsPartial({
+          maker: seller.publicKey,
+          bidder: buyer.publicKey,
+          makerMint: mint,
+          marketplace,
+          offer: findOffer(findListing(mint), buyer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+
+    it("Escrows the offer and settles it with the fee split", async () => {
+      const mint = await createNft();
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+      await makeOffer(mint, LAMPORTS_PER_SOL);
+
+      const offer = findOffer(findListing(mint), buyer.publicKey);
+      const state = await program.account.offer.fetch(offer);
+      assert.equal(state.amount.toNumber(), LAMPORTS_PER_SOL);
+
+      const treasuryBefore = await lamports(treasury);
+      await acceptOffer(mint);
+
+      // 5% fee after the admin tests.
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(LAMPORTS_PER_SOL / 20)
+      );
+      assert.equal(await balance(ata(mint, buyer.publicKey)), BigInt(1));
+      assert.isNull(await context.banksClient.getAccount(offer));
+      assert.isNull(await context.banksClient.getAccount(findListing(mint)));
+    });
+
+    it("Refunds a cancelled offer", async () => {
+      const mint = await createNft();
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+      await makeOffer(mint, LAMPORTS_PER_SOL);
+
+      const offer = findOffer(findListing(mint), buyer.publicKey);
+      const escrowed = await lamports(offer);
+      const before = await lamports(buyer.publicKey);
+
+      await program.methods
+        .cancelOffer()
+        .accountsPartial({ bidder: buyer.publicKey, offer })
+        .signers([buyer])
+        .rpc();
+
+      assert.isNull(await context.banksClient.getAccount(offer));
+      assert.equal(await lamports(buyer.publicKey), before + escrowed);
+    });
+
+    it("Rejects accepting an expired offer", async () => {
+      const mint = await createNft();
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+      await makeOffer(mint, LAMPORTS_PER_SOL, 60);
+
+      await warpTo(context, (await now(context)) + 61);
+      await expectError(acceptOffer(mint), "OfferExpired");
+    });
+  });
+
+  describe("collection bids", () => {
+    const findBid = (bidder: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("bid"),
+          marketplace.toBuffer(),
+          collection.toBuffer(),
+          bidder.toBuffer(),
+        ],
+        program.programId
+      )[0];
+
+    const acceptBid = (mint: PublicKey, collectionMint = collection) =>
+      program.methods
+        .acceptCollectionBid()
+        .accountsPartial({
+          maker: seller.publicKey,
+          bidder: buyer.publicKey,
+          marketplace,
+          makerMint: mint,
+          collectionMint,
+          bid: findBid(buyer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+
+    before(async () => {
+      await program.methods
+        .makeCollectionBid(
+          new BN(LAMPORTS_PER_SOL),
+          new BN(2),
+          new BN((await now(context)) + DAY)
+        )
+        .accountsPartial({
+          bidder: buyer.publicKey,
+          marketplace,
+          collectionMint: collection,
+        })
+        .signers([buyer])
+        .rpc();
+    });
+
+    it("Fills the bid one NFT at a time", async () => {
+      const first = await createNft();
+      await acceptBid(first);
+
+      assert.equal(await balance(ata(first, buyer.publicKey)), BigInt(1));
+      const state = await program.account.collectionBid.fetch(
+        findBid(buyer.publicKey)
+      );
+      assert.equal(state.quantity.toNumber(), 1);
+
+      const second = await createNft();
+      await acceptBid(second);
+      assert.isNull(
+        await context.banksClient.getAccount(findBid(buyer.publicKey))
+      );
+    });
+
+    it("Rejects NFTs without a verified collection", async () => {
+      await program.methods
+        .makeCollectionBid(
+          new BN(LAMPORTS_PER_SOL),
+          new BN(1),
+          new BN((await now(context)) + DAY)
+        )
+        .accountsPartial({
+          bidder: buyer.publicKey,
+          marketplace,
+          collectionMint: collection,
+        })
+        .signers([buyer])
+        .rpc();
+
+      const unverified = await createNft(seller, { verified: false });
+      await expectError(acceptBid(unverified), "CollectionMismatch");
+      const uncollected = await createNft(seller, { collection: null });
+      await expectError(acceptBid(uncollected), "CollectionMismatch");
+
+      await program.methods
+        .cancelCollectionBid()
+        .accountsPartial({
+          bidder: buyer.publicKey,
+          bid: findBid(buyer.publicKey),
+        })
+        .signers([buyer])
+        .rpc();
+      assert.isNull(
+        await context.banksClient.getAccount(findBid(buyer.publicKey))
+      );
+    });
+  });
+
+  describe("english auctions", () => {
+    const rival = Keypair.generate();
+
+    const findListing = (mint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [marketplace.toBuffer(), mint.toBuffer()],
+        program.programId
+      )[0];
+
+    const createAuction = async (mint: PublicKey, reserve: number) => {
+      const start = await now(context);
+      await program.methods
+        .createAuction(
+          new BN(reserve),
+          new BN(start),
+          new BN(start + DAY),
+          1_000
+        )
+        .accountsPartial({
+          maker: seller.publicKey,
+          marketplace,
+          makerMint: mint,
+          collectionMint: collection,
+          ...solPricing,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+      return findListing(mint);
+    };
+
+    const placeBid = (
+      listing: PublicKey,
+      bidder: Keypair,
+      amount: number,
+      previousBidder: PublicKey | null = null
+    ) =>
+      program.methods
+        .placeBid(new BN(amount))
+        .accountsPartial({
+          bidder: bidder.publicKey,
+          marketplace,
+          listing,
+          previousBidder,
+        })
+        .signers([bidder])
+        .rpc();
+
+    const settle = (mint: PublicKey, recipient: PublicKey) =>
+      program.methods
+        .settleAuction()
+        .accountsPartial({
+          payer: admin,
+          maker: seller.publicKey,
+          recipient,
+          makerMint: mint,
+          marketplace,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    const auctionEnd = async (listing: PublicKey) => {
+      const state = await program.account.listing.fetch(listing);
+      return state.kind.english[0].end.toNumber();
+    };
+
+    before(async () => {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: admin,
+            toPubkey: rival.publicKey,
+            lamports: 10 * LAMPORTS_PER_SOL,
+          })
+        )
+      );
+    });
+
+    it("Runs through outbids, an extension and settlement", async () => {
+      const mint = await createNft();
+      const listing = await createAuction(mint, LAMPORTS_PER_SOL);
+
+      await expectError(
+        placeBid(listing, buyer, LAMPORTS_PER_SOL - 1),
+        "BidTooLow"
+      );
+      await placeBid(listing, buyer, LAMPORTS_PER_SOL);
+
+      // 10% minimum increment.
+      await expectError(
+        placeBid(listing, rival, 1_050_000_000, buyer.publicKey),
+        "BidTooLow"
+      );
+      await expectError(
+        placeBid(listing, rival, 1_100_000_000),
+        "InvalidPreviousBidder"
+      );
+
+      const buyerBefore = await lamports(buyer.publicKey);
+      await placeBid(listing, rival, 1_100_000_000, buyer.publicKey);
+      assert.equal(
+        await lamports(buyer.publicKey),
+        buyerBefore + BigInt(LAMPORTS_PER_SOL)
+      );
+
+      await expectError(
+        program.methods
+          .delist()
+          .accountsPartial({
+            maker: seller.publicKey,
+            marketplace,
+            makerMint: mint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([seller])
+          .rpc(),
+        "AuctionHasBids"
+      );
+      await expectError(settle(mint, rival.publicKey), "AuctionNotEnded");
+
+      // A bid in the last minute pushes the end out to ten minutes.
+      await warpTo(context, (await auctionEnd(listing)) - 60);
+      await placeBid(listing, buyer, 1_210_000_000, rival.publicKey);
+      assert.equal(await auctionEnd(listing), (await now(context)) + 600);
+
+      await warpTo(context, await auctionEnd(listing));
+      await expectError(settle(mint, rival.publicKey), "InvalidRecipient");
+
+      const treasuryBefore = await lamports(treasury);
+      await settle(mint, buyer.publicKey);
+
+      assert.equal(await balance(ata(mint, buyer.publicKey)), BigInt(1));
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(1_210_000_000 / 20)
+      );
+      assert.isNull(await context.banksClient.getAccount(listing));
+    });
+
+    it("Returns the NFT to the maker when nobody bids", async () => {
+      const mint = await createNft();
+      const listing = await createAuction(mint, LAMPORTS_PER_SOL);
+
+      await warpTo(context, await auctionEnd(listing));
+      await expectError(
+        placeBid(listing, buyer, LAMPORTS_PER_SOL),
+        "AuctionEnded"
+      );
+      await settle(mint, seller.publicKey);
+
+      assert.equal(await balance(ata(mint, seller.publicKey)), BigInt(1));
+    });
+
+    it("Cannot be bought at a fixed price", async () => {
+      const mint = await createNft();
+      await createAuction(mint, LAMPORTS_PER_SOL);
+      await expectError(purchase(mint), "NotFixedPrice");
+    });
+  });
+
+  describe("dutch auctions", () => {
+    const createDutchAuction = async (
+      mint: PublicKey,
+      curve: { linear: {} } | { exponential: { halfLife: anchor.BN } }
+    ) => {
+      const start = await now(context);
+      await program.methods
+        .createDutchAuction(
+          new BN(2 * LAMPORTS_PER_SOL),
+          new BN(LAMPORTS_PER_SOL),
+          new BN(start),
+          new BN(start + 1_000),
+          curve
+        )
+        .accountsPartial({
+          maker: seller.publicKey,
+          marketplace,
+          makerMint: mint,
+          collectionMint: collection,
+          ...solPricing,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+      return start;
+    };
+
+    const purchaseAt = (mint: PublicKey, maxPrice: number) =>
+      purchase(mint, null, buyer, seller.publicKey, new BN(maxPrice));
+
+    it("Decays linearly and honours the buyer's maximum", async () => {
+      const mint = await createNft();
+      const start = await createDutchAuction(mint, { linear: {} });
+
+      // Halfway down from 2 SOL to 1 SOL.
+      await warpTo(context, start + 500);
+      await expectError(purchaseAt(mint, 1_499_999_999), "PriceAboveMax");
+
+      const treasuryBefore = await lamports(treasury);
+      await purchaseAt(mint, 1_500_000_000);
+
+      assert.equal(await balance(ata(mint, buyer.publicKey)), BigInt(1));
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(1_500_000_000 / 20)
+      );
+    });
+
+    it("Halves the distance to the floor every half-life", async () => {
+      const mint = await createNft();
+      const start = await createDutchAuction(mint, {
+        exponential: { halfLife: new BN(100) },
+      });
+
+      // One and a half half-lives: 1 SOL above the floor becomes 0.375.
+      await warpTo(context, start + 150);
+      await expectError(purchaseAt(mint, 1_374_999_999), "PriceAboveMax");
+
+      const treasuryBefore = await lamports(treasury);
+      await purchaseAt(mint, 1_375_000_000);
+
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(1_375_000_000 / 20)
+      );
+    });
+
+    it("Sells at the floor once the auction ends", async () => {
+      const mint = await createNft();
+      const start = await createDutchAuction(mint, { linear: {} });
+
+      await warpTo(context, start + 5_000);
+      await purchaseAt(mint, LAMPORTS_PER_SOL);
+      assert.equal(await balance(ata(mint, buyer.publicKey)), BigInt(1));
+    });
+  });
+
+  describe("spl payments", () => {
+    let usdc: PublicKey;
+
+    before(async () => {
+      const mint = Keypair.generate();
+      const rent = await context.banksClient.getRent();
+      const buyerAta = ata(mint.publicKey, buyer.publicKey);
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: admin,
+            newAccountPubkey: mint.publicKey,
+            space: MINT_SIZE,
+            lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+            programId: TOKEN_PROGRAM_ID,
+          }),
+          createInitializeMint2Instruction(mint.publicKey, 6, admin, null),
+          createAssociatedTokenAccountIdempotentInstruction(
+            admin,
+            buyerAta,
+            buyer.publicKey,
+            mint.publicKey
+          ),
+          createMintToInstruction(
+            mint.publicKey,
+            buyerAta,
+            admin,
+            1_000_000_000
+          )
+        ),
+        [mint]
+      );
+      usdc = mint.publicKey;
+    });
+
+    it("Only lists in mints the admin accepts", async () => {
+      const mint = await createNft();
+      await expectError(
+        program.methods
+          .list(new BN(100_000_000))
+          .accountsPartial({
+            maker: seller.publicKey,
+            marketplace,
+            makerMint: mint,
+            collectionMint: collection,
+            paymentMint: usdc,
+            acceptedMint: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([seller])
+          .rpc(),
+        "PaymentMintNotAllowed"
+      );
+    });
+
+    it("Only lets the admin accept payment mints", async () => {
+      await expectError(
+        program.methods
+          .addAcceptedMint()
+          .accountsPartial({
+            admin: buyer.publicKey,
+            marketplace,
+            paymentMint: usdc,
+          })
+          .signers([buyer])
+          .rpc(),
+        "Unauthorized"
+      );
+    });
+
+    it("Settles a token-priced purchase with the fee split", async () => {
+      await program.methods
+        .addAcceptedMint()
+        .accountsPartial({ admin, marketplace, paymentMint: usdc })
+        .rpc();
+
+      const mint = await createNft();
+      await list(mint, 100_000_000, seller, usdc);
+
+      const listing = await program.account.listing.fetch(
+        PublicKey.findProgramAddressSync(
+          [marketplace.toBuffer(), mint.toBuffer()],
+          program.programId
+        )[0]
+      );
+      assert.isTrue(listing.paymentMint.equals(usdc));
+
+      await purchase(mint, null, buyer, seller.publicKey, U64_MAX, usdc);
+
+      // 5% of 100 USDC to the treasury, the rest to the seller.
+      assert.equal(await balance(ata(mint, buyer.publicKey)), BigInt(1));
+      assert.equal(
+        await balance(ata(usdc, buyer.publicKey)),
+        BigInt(900_000_000)
+      );
+      assert.equal(
+        await balance(ata(usdc, seller.publicKey)),
+        BigInt(95_000_000)
+      );
+      assert.equal(await balance(ata(usdc, treasury)), BigInt(5_000_000));
+    });
+
+    it("Rejects a token-priced purchase paid in lamports", async () => {
+      const mint = await createNft();
+      await list(mint, 100_000_000, seller, usdc);
+      await expectError(purchase(mint), "InvalidPaymentAccounts");
+    });
+
+    it("Lets the admin withdraw treasury tokens", async () => {
+      const destination = ata(usdc, admin);
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          createAssociatedTokenAccountIdempotentInstruction(
+            admin,
+            destination,
+            admin,
+            usdc
+          )
+        )
+      );
+
+      await program.methods
+        .withdrawTreasuryTokens(new BN(5_000_000))
+        .accountsPartial({
+          admin,
+          marketplace,
+          paymentMint: usdc,
+          destination,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      assert.equal(await balance(destination), BigInt(5_000_000));
+      assert.equal(await balance(ata(usdc, treasury)), BigInt(0));
+    });
+
+    it("Stops listings and purchases once a mint is removed", async () => {
+      const listed = await createNft();
+      await list(listed, 100_000_000, seller, usdc);
+
+      await program.methods
+        .removeAcceptedMint()
+        .accountsPartial({
+          admin,
+          marketplace,
+          acceptedMint: findAcceptedMint(usdc),
+        })
+        .rpc();
+
+      const mint = await createNft();
+      await expectError(
+        list(mint, 100_000_000, seller, usdc),
+        "AccountNotInitialized"
+      );
+
+      const buy = (acceptedMint: PublicKey | null) =>
+        purchase(
+          listed,
+          null,
+          buyer,
+          seller.publicKey,
+          U64_MAX,
+          usdc,
+          acceptedMint
+        );
+      await expectError(buy(findAcceptedMint(usdc)), "AccountNotInitialized");
+      await expectError(buy(null), "PaymentMintNotAllowed");
+    });
+  });
+
+  describe("royalties", () => {
+    const verifiedCreator = Keypair.generate().publicKey;
+    const unverifiedCreator = Keypair.generate().publicKey;
+
+    const creators = [
+      { address: verifiedCreator, verified: true, share: 60 },
+      { address: unverifiedCreator, verified: false, share: 40 },
+    ];
+
+    const purchaseWithCreators = (mint: PublicKey, accounts: PublicKey[]) =>
+      program.methods
+        .purchase(U64_MAX)
+        .accountsPartial({
+          taker: buyer.publicKey,
+          maker: seller.publicKey,
+          makerMint: mint,
+          marketplace,
+          credit: null,
+          takerRewards: ata(rewards, buyer.publicKey),
+          makerRewards: ata(rewards, seller.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          paymentMint: null,
+          acceptedMint: null,
+          takerPaymentAta: null,
+          makerPaymentAta: null,
+          treasuryPaymentAta: null,
+          paymentTokenProgram: null,
+        })
+        .remainingAccounts(
+          accounts.map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: true,
+          }))
+        )
+        .signers([buyer])
+        .rpc();
+
+    it("Snapshots verified creators at list time", async () => {
+      const mint = await createNft(seller, {
+        sellerFeeBasisPoints: 500,
+        creators,
+      });
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+
+      const listing = await program.account.listing.fetch(
+        PublicKey.findProgramAddressSync(
+          [marketplace.toBuffer(), mint.toBuffer()],
+          program.programId
+        )[0]
+      );
+      assert.equal(listing.royalty.sellerFeeBasisPoints, 500);
+      assert.equal(listing.royalty.creators.length, 1);
+      assert.isTrue(
+        listing.royalty.creators[0].address.equals(verifiedCreator)
+      );
+    });
+
+    it("Pays verified creators their share of the royalty", async () => {
+      const mint = await createNft(seller, {
+        sellerFeeBasisPoints: 500,
+        creators,
+      });
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+
+      await expectError(
+        purchaseWithCreators(mint, []),
+        "InvalidCreatorAccounts"
+      );
+      await expectError(
+        purchaseWithCreators(mint, [unverifiedCreator]),
+        "InvalidCreatorAccounts"
+      );
+
+      // Raising the royalty after listing does not change the sale.
+      await setAccount(
+        findMetadata(mint),
+        metadataData(mint, collection, true, 10_000, creators)
+      );
+
+      const creatorBefore = await lamports(verifiedCreator);
+      const treasuryBefore = await lamports(treasury);
+      await purchaseWithCreators(mint, [verifiedCreator]);
+
+      // 5% royalty on 2 SOL, 60% of it to the only verified creator.
+      assert.equal(
+        await lamports(verifiedCreator),
+        creatorBefore + BigInt(60_000_000)
+      );
+      assert.equal(await lamports(unverifiedCreator), BigInt(0));
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(100_000_000)
+      );
+    });
+
+    it("Leaves royalties too small to fund a creator with the seller", async () => {
+      const unfunded = Keypair.generate().publicKey;
+      const mint = await createNft(seller, {
+        sellerFeeBasisPoints: 500,
+        creators: [
+          { address: unfunded, verified: true, share: 60 },
+          { address: unverifiedCreator, verified: false, share: 40 },
+        ],
+      });
+      await list(mint, 10_000_000);
+
+      const sellerBefore = await lamports(seller.publicKey);
+      const treasuryBefore = await lamports(treasury);
+      await purchaseWithCreators(mint, [unfunded]);
+
+      // 60% of a 5% royalty on 0.01 SOL is below rent exemption.
+      assert.equal(await lamports(unfunded), BigInt(0));
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(500_000)
+      );
+      assert.isAtLeast(
+        Number((await lamports(seller.publicKey)) - sellerBefore),
+        9_500_000
+      );
+    });
+
+    it("Rejects royalties the fee leaves no room for", async () => {
+      // 96% royalty on top of the 5% fee.
+      const mint = await createNft(seller, {
+        sellerFeeBasisPoints: 9_600,
+        creators,
+      });
+      await expectError(list(mint, LAMPORTS_PER_SOL), "RoyaltyTooHigh");
+    });
+
+    it("Caps royalties at what a raised fee leaves of the price", async () => {
+      const setFee = (fee: number) =>
+        program.methods
+          .updateFee(fee)
+          .accountsPartial({ admin, marketplace })
+          .rpc();
+
+      const mint = await createNft(seller, {
+        sellerFeeBasisPoints: 9_500,
+        creators,
+      });
+      await list(mint, 2 * LAMPORTS_PER_SOL);
+      await setFee(1_000);
 
-  const program = anchor.workspace.AnchorMarketplace as Program<AnchorMarketplace>;
+      const creatorBefore = await lamports(verifiedCreator);
+      const treasuryBefore = await lamports(treasury);
+      await purchaseWithCreators(mint, [verifiedCreator]);
+      await setFee(500);
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+      // The 10% fee is paid in full and leaves 1.8 SOL of the 1.9 SOL
+      // royalty, 60% of it to the only verified creator.
+      assert.equal(
+        await lamports(treasury),
+        treasuryBefore + BigInt(200_000_000)
+      );
+      assert.equal(
+        await lamports(verifiedCreator),
+        creatorBefore + BigInt(1_080_000_000)
+      );
+    });
   });
 });
@@ -0,0 +1,15 @@
+/// Upper bound on the protocol fee charged on takes, in basis points.
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// How long the arbiter has to rule on a dispute before the seller can claim
+/// the full amount by default.
+pub const DISPUTE_RULING_TIMEOUT: i64 = 14 * 24 * 60 * 60;
+
+/// Most tranches a milestone escrow can be split into.
+pub const MAX_TRANCHES: usize = 8;
+
+/// Offers listed per mint pair in an `OfferBook`.
+pub const OFFER_BOOK_CAPACITY: usize = 64;
+
+/// Most mints a basket offer can give or ask for on each side.
+pub const MAX_BASKET_LEGS: usize = 4;
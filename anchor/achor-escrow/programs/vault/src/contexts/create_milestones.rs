@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{errors::EscrowError, Milestone, TrancheInput};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateMilestones<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Milestone::INIT_SPACE,
+        seeds = [b"milestone", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = milestone,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateMilestones<'info> {
+    /// Records the tranches and returns the total to deposit.
+    pub fn save_milestones(
+        &mut self,
+        seed: u64,
+        payee: Pubkey,
+        arbiter: Pubkey,
+        tranches: Vec<TrancheInput>,
+        bumps: &CreateMilestonesBumps,
+    ) -> Result<u64> {
+        let maker = self.maker.key();
+        require!(
+            payee != maker && arbiter != maker && arbiter != payee,
+            EscrowError::InvalidParties
+        );
+        let (tranches, total) = Milestone::build_tranches(tranches)?;
+
+        self.milestone.set_inner(Milestone {
+            seed,
+            maker: self.maker.key(),
+            payee,
+            arbiter,
+            mint: self.mint.key(),
+            tranches,
+            released: 0,
+            refunded: 0,
+            bump: bumps.milestone,
+        });
+        Ok(total)
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::Milestone;
+
+#[derive(Accounts)]
+pub struct DisputeTranche<'info> {
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"milestone", maker.key().as_ref(), milestone.seed.to_le_bytes().as_ref()],
+        bump = milestone.bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+}
+
+impl<'info> DisputeTranche<'info> {
+    /// Stops a pending tranche from being claimed at its deadline and hands
+    /// it to the arbiter.
+    pub fn dispute_tranche(&mut self, index: u8) -> Result<()> {
+        self.milestone.dispute(index)
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::OfferBook;
+
+/// Anyone can open the book for a mint pair ahead of its first offer.
+#[derive(Accounts)]
+pub struct InitOfferBook<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<OfferBook>(),
+        seeds = [b"book", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump
+    )]
+    pub offer_book: AccountLoader<'info, OfferBook>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitOfferBook<'info> {
+    pub fn init_offer_book(&mut self, bumps: &InitOfferBookBumps) -> Result<()> {
+        let mut book = self.offer_book.load_init()?;
+        book.mint_a = self.mint_a.key();
+        book.mint_b = self.mint_b.key();
+        book.bump = bumps.offer_book;
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::EscrowError, program::Escrow, EscrowConfig};
+
+/// Only the upgrade authority can create the config, so nobody can claim the
+/// admin and treasury between deploy and setup.
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, Escrow>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ EscrowError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + EscrowConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, EscrowConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(
+        &mut self,
+        fee_bps: u16,
+        treasury: Pubkey,
+        bumps: &InitializeConfigBumps,
+    ) -> Result<()> {
+        EscrowConfig::validate_fee(fee_bps)?;
+
+        self.config.set_inner(EscrowConfig {
+            admin: self.admin.key(),
+            fee_bps,
+            treasury,
+            bump: bumps.config,
+        });
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use anchor_spl::{
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::Escrow;
+use crate::{errors::EscrowError, events::OfferCreated, Escrow, OfferBook};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -48,6 +48,15 @@ pub struct Make<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = 8 + std::mem::size_of::<OfferBook>(),
+        seeds = [b"book", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump
+    )]
+    pub offer_book: AccountLoader<'info, OfferBook>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -56,18 +65,64 @@ pub struct Make<'info> {
 }
 
 impl<'info> Make<'info> {
-    pub fn save_escrow(&mut self, seed: u64, receive: u64, bumps: &MakeBumps) -> Result<()> {
+    pub fn save_escrow(
+        &mut self,
+        seed: u64,
+        receive: u64,
+        expires_at: i64,
+        taker: Option<Pubkey>,
+        require_authorization: bool,
+        bumps: &MakeBumps,
+    ) -> Result<()> {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            EscrowError::InvalidExpiry
+        );
+
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
             receive,
+            expires_at,
+            taker,
+            require_authorization,
+            version: 0,
             bump: bumps.escrow,
         });
         Ok(())
     }
 
+    /// Lists the funded offer in the book, opening the book if this is the
+    /// pair's first offer, and announces it.
+    pub fn list_offer(&mut self, bumps: &MakeBumps) -> Result<()> {
+        self.vault.reload()?;
+        let mut book = match self.offer_book.load_mut() {
+            Ok(book) => book,
+            Err(_) => {
+                let mut book = self.offer_book.load_init()?;
+                book.mint_a = self.mint_a.key();
+                book.mint_b = self.mint_b.key();
+                book.bump = bumps.offer_book;
+                book
+            }
+        };
+        book.list(self.escrow.key(), &self.escrow, self.vault.amount);
+        drop(book);
+
+        emit!(OfferCreated {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            deposit: self.vault.amount,
+            receive: self.escrow.receive,
+            expires_at: self.escrow.expires_at,
+        });
+        Ok(())
+    }
+
     pub fn deposit(&mut self, deposit: u64) -> Result<()> {
         let transfer_accounts = TransferChecked {
             from: self.maker_ata_a.to_account_info(),
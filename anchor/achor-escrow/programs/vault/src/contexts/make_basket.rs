@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{transfer_checked, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    errors::EscrowError,
+    legs::{check_leg_ata, leg_mint_decimals},
+    Basket, BasketLeg,
+};
+
+/// Remaining accounts: `[mint, maker_ata, vault]` for each offered leg, in order.
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeBasket<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Basket::INIT_SPACE,
+        seeds = [b"basket", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub basket: Account<'info, Basket>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeBasket<'info> {
+    pub fn save_basket(
+        &mut self,
+        seed: u64,
+        offered: Vec<BasketLeg>,
+        requested: Vec<BasketLeg>,
+        bumps: &MakeBasketBumps,
+    ) -> Result<()> {
+        Basket::validate_legs(&offered)?;
+        Basket::validate_legs(&requested)?;
+
+        self.basket.set_inner(Basket {
+            seed,
+            maker: self.maker.key(),
+            offered,
+            requested,
+            bump: bumps.basket,
+        });
+        Ok(())
+    }
+
+    pub fn deposit(&self, remaining: &[AccountInfo<'info>]) -> Result<()> {
+        require_eq!(
+            remaining.len(),
+            self.basket.offered.len() * 3,
+            EscrowError::InvalidBasketAccounts
+        );
+
+        let token_program = self.token_program.key();
+        for (leg, accounts) in self.basket.offered.iter().zip(remaining.chunks(3)) {
+            let (mint, maker_ata, vault) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            let decimals = leg_mint_decimals(mint, &leg.mint, &token_program)?;
+            check_leg_ata(maker_ata, self.maker.key, &leg.mint, &token_program)?;
+            check_leg_ata(vault, &self.basket.key(), &leg.mint, &token_program)?;
+
+            let create_accounts = Create {
+                payer: self.maker.to_account_info(),
+                associated_token: vault.clone(),
+                authority: self.basket.to_account_info(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            let transfer_accounts = TransferChecked {
+                from: maker_ata.clone(),
+                mint: mint.clone(),
+                to: vault.clone(),
+                authority: self.maker.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+            transfer_checked(cpi_ctx, leg.amount, decimals)?;
+        }
+        Ok(())
+    }
+}
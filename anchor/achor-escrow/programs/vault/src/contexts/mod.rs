@@ -6,3 +6,48 @@ pub use take::*;
 
 pub mod refund;
 pub use refund::*;
+
+pub mod take_partial;
+pub use take_partial::*;
+
+pub mod crank_refund;
+pub use crank_refund::*;
+
+pub mod update_offer;
+pub use update_offer::*;
+
+pub mod initialize_config;
+pub use initialize_config::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod open_service;
+pub use open_service::*;
+
+pub mod raise_dispute;
+pub use raise_dispute::*;
+
+pub mod settle_service;
+pub use settle_service::*;
+
+pub mod create_milestones;
+pub use create_milestones::*;
+
+pub mod dispute_tranche;
+pub use dispute_tranche::*;
+
+pub mod settle_tranche;
+pub use settle_tranche::*;
+
+pub mod init_offer_book;
+pub use init_offer_book::*;
+
+pub mod make_basket;
+pub use make_basket::*;
+
+pub mod take_basket;
+pub use take_basket::*;
+
+pub mod refund_basket;
+pub use refund_basket::*;
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{errors::EscrowError, ServiceEscrow, ServiceStatus};
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct OpenService<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + ServiceEscrow::INIT_SPACE,
+        seeds = [b"service", buyer.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub service: Account<'info, ServiceEscrow>,
+
+    #[account(
+        init,
+        payer = buyer,
+        associated_token::mint = mint,
+        associated_token::authority = service,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenService<'info> {
+    pub fn save_service(
+        &mut self,
+        seed: u64,
+        seller: Pubkey,
+        arbiter: Pubkey,
+        amount: u64,
+        bumps: &OpenServiceBumps,
+    ) -> Result<()> {
+        let buyer = self.buyer.key();
+        require!(
+            amount > 0 && seller != buyer && arbiter != buyer && arbiter != seller,
+            EscrowError::InvalidParties
+        );
+
+        self.service.set_inner(ServiceEscrow {
+            seed,
+            buyer,
+            seller,
+            arbiter,
+            mint: self.mint.key(),
+            amount,
+            status: ServiceStatus::Funded,
+            bump: bumps.service,
+        });
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.buyer_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+}
@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::ServiceEscrow;
+
+#[derive(Accounts)]
+pub struct RaiseDispute<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"service", service.buyer.as_ref(), service.seed.to_le_bytes().as_ref()],
+        bump = service.bump
+    )]
+    pub service: Account<'info, ServiceEscrow>,
+}
+
+impl<'info> RaiseDispute<'info> {
+    pub fn raise_dispute(&mut self) -> Result<()> {
+        self.service.raise_dispute(self.caller.key)
+    }
+}
@@ -10,7 +10,10 @@ use anchor_spl::{
 
 
 
-use crate::state::Escrow;
+use crate::{
+    events::OfferRefunded,
+    state::{Escrow, OfferBook},
+};
 
 #[derive(Accounts)]
 pub struct Refund<'info> {
@@ -43,11 +46,23 @@ pub struct Refund<'info> {
         associated_token::token_program = token_program
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book", escrow.mint_a.as_ref(), escrow.mint_b.as_ref()],
+        bump = offer_book.load()?.bump
+    )]
+    pub offer_book: AccountLoader<'info, OfferBook>,
     associated_token_program: Program<'info, AssociatedToken>,
     token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
 impl<'info> Refund<'info> {
+    pub fn delist(&mut self) -> Result<()> {
+        self.offer_book.load_mut()?.remove(&self.escrow.key());
+        Ok(())
+    }
+
     pub fn refund_and_close_vault(&mut self) -> Result<()> {
     let signer_seeds: [&[&[u8]]; 1] = [&[
         b"escrow",
@@ -70,6 +85,12 @@ impl<'info> Refund<'info> {
     );
     transfer_checked(ctx, self.vault.amount, self.mint_a.decimals)?;
 
+    emit!(OfferRefunded {
+        escrow: self.escrow.key(),
+        maker: self.maker.key(),
+        amount: self.vault.amount,
+    });
+
     let close_accounts = CloseAccount{
         account: self.vault.to_account_info(),
         destination: self.maker.to_account_info(),
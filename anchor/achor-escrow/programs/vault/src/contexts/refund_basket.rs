@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    errors::EscrowError,
+    legs::{check_leg_ata, leg_mint_decimals, token_amount},
+    Basket,
+};
+
+/// Remaining accounts: `[mint, vault, maker_ata]` for each offered leg, in order.
+#[derive(Accounts)]
+pub struct RefundBasket<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"basket", maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump
+    )]
+    pub basket: Account<'info, Basket>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundBasket<'info> {
+    pub fn refund_and_close_vaults(&self, remaining: &[AccountInfo<'info>]) -> Result<()> {
+        require_eq!(
+            remaining.len(),
+            self.basket.offered.len() * 3,
+            EscrowError::InvalidBasketAccounts
+        );
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"basket",
+            self.maker.to_account_info().key.as_ref(),
+            &self.basket.seed.to_le_bytes()[..],
+            &[self.basket.bump],
+        ]];
+
+        let token_program = self.token_program.key();
+        for (leg, accounts) in self.basket.offered.iter().zip(remaining.chunks(3)) {
+            let (mint, vault, maker_ata) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            let decimals = leg_mint_decimals(mint, &leg.mint, &token_program)?;
+            check_leg_ata(vault, &self.basket.key(), &leg.mint, &token_program)?;
+            check_leg_ata(maker_ata, self.maker.key, &leg.mint, &token_program)?;
+
+            let create_accounts = Create {
+                payer: self.maker.to_account_info(),
+                associated_token: maker_ata.clone(),
+                authority: self.maker.to_account_info(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            };
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                create_accounts,
+            ))?;
+
+            let transfer_accounts = TransferChecked {
+                from: vault.clone(),
+                mint: mint.clone(),
+                to: maker_ata.clone(),
+                authority: self.basket.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_accounts,
+                &signer_seeds,
+            );
+            transfer_checked(ctx, token_amount(vault)?, decimals)?;
+
+            let close_accounts = CloseAccount {
+                account: vault.clone(),
+                destination: self.maker.to_account_info(),
+                authority: self.basket.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                close_accounts,
+                &signer_seeds,
+            );
+            close_account(ctx)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{errors::EscrowError, ServiceEscrow};
+
+/// Shared by `release`, `rule` and `claim_default`; each handler checks that
+/// `caller` may settle before paying out.
+#[derive(Accounts)]
+pub struct SettleService<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub buyer: SystemAccount<'info>,
+
+    pub seller: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = seller,
+        associated_token::token_program = token_program
+    )]
+    pub seller_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        close = buyer,
+        has_one = buyer,
+        has_one = seller,
+        has_one = mint,
+        seeds = [b"service", buyer.key().as_ref(), service.seed.to_le_bytes().as_ref()],
+        bump = service.bump
+    )]
+    pub service: Account<'info, ServiceEscrow>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = service,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleService<'info> {
+    /// Buyer accepts delivery and pays the seller in full.
+    pub fn release(&mut self) -> Result<()> {
+        self.service.check_release(self.caller.key)?;
+        self.payout(self.vault.amount)
+    }
+
+    pub fn rule(&mut self, seller_amount: u64) -> Result<()> {
+        self.service.check_ruling(self.caller.key)?;
+        self.payout(seller_amount)
+    }
+
+    /// Anyone can settle in the seller's favour once the arbiter missed the
+    /// ruling deadline.
+    pub fn claim_default(&mut self) -> Result<()> {
+        self.service.check_default()?;
+        self.payout(self.vault.amount)
+    }
+
+    /// Sends `seller_amount` to the seller and whatever is left to the buyer,
+    /// then closes the vault.
+    fn payout(&mut self, seller_amount: u64) -> Result<()> {
+        require!(
+            seller_amount <= self.vault.amount,
+            EscrowError::InvalidSplit
+        );
+        let buyer_amount = self.vault.amount - seller_amount;
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"service",
+            self.buyer.to_account_info().key.as_ref(),
+            &self.service.seed.to_le_bytes()[..],
+            &[self.service.bump],
+        ]];
+
+        for (to, amount) in [
+            (self.seller_ata.to_account_info(), seller_amount),
+            (self.buyer_ata.to_account_info(), buyer_amount),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+
+            let transfer_accounts = TransferChecked {
+                from: self.vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to,
+                authority: self.service.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_accounts,
+                &signer_seeds,
+            );
+            transfer_checked(ctx, amount, self.mint.decimals)?;
+        }
+
+        let close_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.buyer.to_account_info(),
+            authority: self.service.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_accounts,
+            &signer_seeds,
+        );
+        close_account(ctx)
+    }
+}
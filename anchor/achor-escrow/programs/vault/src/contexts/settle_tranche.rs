@@ -0,0 +1,202 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{errors::EscrowError, Milestone, TrancheStatus};
+
+/// Shared by `approve_tranche`, `claim_tranche`, `refund_tranche` and
+/// `rule_tranche`. Once
+/// every tranche is settled the vault and milestone account are closed.
+#[derive(Accounts)]
+pub struct SettleTranche<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    pub payee: SystemAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint,
+        associated_token::authority = payee,
+        associated_token::token_program = token_program
+    )]
+    pub payee_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = payee,
+        has_one = mint,
+        seeds = [b"milestone", maker.key().as_ref(), milestone.seed.to_le_bytes().as_ref()],
+        bump = milestone.bump
+    )]
+    pub milestone: Account<'info, Milestone>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = milestone,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleTranche<'info> {
+    /// Maker accepts a pending or disputed tranche.
+    pub fn approve_tranche(&mut self, index: u8) -> Result<()> {
+        require_keys_eq!(
+            self.caller.key(),
+            self.milestone.maker,
+            EscrowError::NotParty
+        );
+        self.settle(index, TrancheStatus::Released)
+    }
+
+    /// Anyone can release a pending tranche once its deadline has passed, or a
+    /// disputed one the arbiter did not rule on in time.
+    pub fn claim_tranche(&mut self, index: u8) -> Result<()> {
+        self.milestone.check_claimable(index)?;
+        self.settle(index, TrancheStatus::Released)
+    }
+
+    /// Arbiter decides a disputed tranche for the payee or the maker.
+    pub fn rule_tranche(&mut self, index: u8, release: bool) -> Result<()> {
+        self.milestone.check_ruling(index, self.caller.key)?;
+        let outcome = if release {
+            TrancheStatus::Released
+        } else {
+            TrancheStatus::Refunded
+        };
+        self.settle(index, outcome)
+    }
+
+    /// Payee gives up a pending or disputed tranche, returning it to the maker.
+    pub fn refund_tranche(&mut self, index: u8) -> Result<()> {
+        require_keys_eq!(
+            self.caller.key(),
+            self.milestone.payee,
+            EscrowError::NotParty
+        );
+        self.settle(index, TrancheStatus::Refunded)
+    }
+
+    fn settle(&mut self, index: u8, outcome: TrancheStatus) -> Result<()> {
+        let tranche = self.milestone.tranche_mut(index)?;
+        require!(
+            matches!(
+                tranche.status,
+                TrancheStatus::Pending | TrancheStatus::Disputed { .. }
+            ),
+            EscrowError::TrancheNotPending
+        );
+        tranche.status = outcome;
+        let amount = tranche.amount;
+
+        let to = if outcome == TrancheStatus::Released {
+            self.milestone.released = self
+                .milestone
+                .released
+                .checked_add(amount)
+                .ok_or(EscrowError::Overflow)?;
+            self.payee_ata.to_account_info()
+        } else {
+            self.milestone.refunded = self
+                .milestone
+                .refunded
+                .checked_add(amount)
+                .ok_or(EscrowError::Overflow)?;
+            self.maker_ata.to_account_info()
+        };
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"milestone",
+            self.maker.to_account_info().key.as_ref(),
+            &self.milestone.seed.to_le_bytes()[..],
+            &[self.milestone.bump],
+        ]];
+
+        let transfer_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to,
+            authority: self.milestone.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            transfer_accounts,
+            &signer_seeds,
+        );
+        transfer_checked(ctx, amount, self.mint.decimals)?;
+
+        if self.milestone.is_settled() {
+            self.close_vault()?;
+        }
+        Ok(())
+    }
+
+    /// Sweeps any dust back to the maker and closes the vault and milestone.
+    fn close_vault(&mut self) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"milestone",
+            self.maker.to_account_info().key.as_ref(),
+            &self.milestone.seed.to_le_bytes()[..],
+            &[self.milestone.bump],
+        ]];
+
+        self.vault.reload()?;
+        if self.vault.amount > 0 {
+            let transfer_accounts = TransferChecked {
+                from: self.vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                to: self.maker_ata.to_account_info(),
+                authority: self.milestone.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_accounts,
+                &signer_seeds,
+            );
+            transfer_checked(ctx, self.vault.amount, self.mint.decimals)?;
+        }
+
+        let close_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.milestone.to_account_info(),
+        };
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_accounts,
+            &signer_seeds,
+        );
+        close_account(ctx)?;
+
+        self.milestone.close(self.maker.to_account_info())
+    }
+}
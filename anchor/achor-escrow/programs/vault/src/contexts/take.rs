@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::sysvar::instructions};
 
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -8,7 +8,7 @@ use anchor_spl::{
     },
 };
 
-use crate::Escrow;
+use crate::{events::OfferTaken, transfer::transfer_exact, Escrow, EscrowConfig, OfferBook};
 
 #[derive(Accounts)]
 pub struct Take<'info> {
@@ -47,6 +47,24 @@ pub struct Take<'info> {
     )]
     pub maker_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, EscrowConfig>,
+
+    #[account(address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
     #[account(
         mut,
         close = maker,
@@ -66,6 +84,17 @@ pub struct Take<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"book", escrow.mint_a.as_ref(), escrow.mint_b.as_ref()],
+        bump = offer_book.load()?.bump
+    )]
+    pub offer_book: AccountLoader<'info, OfferBook>,
+
+    /// CHECK: address is checked against the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
 
     pub token_program: Interface<'info, TokenInterface>,
@@ -74,17 +103,40 @@ pub struct Take<'info> {
 }
 
 impl<'info> Take<'info> {
-    pub fn deposit(&mut self) -> Result<()> {
-        let transfer_accounts = TransferChecked {
-            from: self.taker_ata_b.to_account_info(),
-            mint: self.mint_b.to_account_info(),
-            to: self.maker_ata_b.to_account_info(),
-            authority: self.taker.to_account_info(),
-        };
+    pub fn delist(&mut self) -> Result<()> {
+        self.offer_book.load_mut()?.remove(&self.escrow.key());
+        Ok(())
+    }
 
-        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+    pub fn check_taker(&self) -> Result<()> {
+        self.escrow.check_taker(
+            &self.escrow.key(),
+            &self.taker.key(),
+            &self.instructions_sysvar,
+        )
+    }
+
+    /// Pays `receive` of `mint_b`: the protocol fee to the treasury and the
+    /// rest to the maker, each arriving in full even for transfer-fee mints.
+    pub fn deposit(&mut self) -> Result<()> {
+        let fee = self.config.fee_for(self.escrow.receive)?;
 
-        transfer_checked(cpi_ctx, self.escrow.receive, self.mint_b.decimals)
+        transfer_exact(
+            self.token_program.to_account_info(),
+            self.taker_ata_b.to_account_info(),
+            self.taker.to_account_info(),
+            &self.mint_b,
+            &mut self.maker_ata_b,
+            self.escrow.receive - fee,
+        )?;
+        transfer_exact(
+            self.token_program.to_account_info(),
+            self.taker_ata_b.to_account_info(),
+            self.taker.to_account_info(),
+            &self.mint_b,
+            &mut self.treasury_ata_b,
+            fee,
+        )
     }
 
     pub fn withdraw_and_close_vault(&mut self) -> Result<()> {
@@ -109,6 +161,15 @@ impl<'info> Take<'info> {
         );
         transfer_checked(ctx, self.vault.amount, self.mint_a.decimals)?;
 
+        emit!(OfferTaken {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            taker: self.taker.key(),
+            amount_a: self.vault.amount,
+            amount_b: self.escrow.receive,
+            fee: self.config.fee_for(self.escrow.receive)?,
+        });
+
         let accounts = CloseAccount {
             account: self.vault.to_account_info(),
             destination: self.taker.to_account_info(),
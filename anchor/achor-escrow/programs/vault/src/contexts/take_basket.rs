@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    errors::EscrowError,
+    legs::{check_leg_ata, leg_mint_decimals, token_amount},
+    Basket,
+};
+
+/// Remaining accounts: `[mint, vault, taker_ata]` for each offered leg, then
+/// `[mint, taker_ata, maker_ata]` for each requested leg. Any failing leg
+/// aborts the whole transaction, so the swap settles completely or not at all.
+#[derive(Accounts)]
+pub struct TakeBasket<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker,
+        seeds = [b"basket", maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump
+    )]
+    pub basket: Account<'info, Basket>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeBasket<'info> {
+    fn create_ata(
+        &self,
+        ata: &AccountInfo<'info>,
+        owner: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let create_accounts = Create {
+            payer: self.taker.to_account_info(),
+            associated_token: ata.clone(),
+            authority: owner.clone(),
+            mint: mint.clone(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            create_accounts,
+        ))
+    }
+
+    pub fn settle(&self, remaining: &[AccountInfo<'info>]) -> Result<()> {
+        let offered_len = self.basket.offered.len() * 3;
+        require_eq!(
+            remaining.len(),
+            offered_len + self.basket.requested.len() * 3,
+            EscrowError::InvalidBasketAccounts
+        );
+        let (offered, requested) = remaining.split_at(offered_len);
+
+        self.pay_maker(requested)?;
+        self.release_vaults(offered)
+    }
+
+    fn pay_maker(&self, accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let token_program = self.token_program.key();
+        for (leg, accounts) in self.basket.requested.iter().zip(accounts.chunks(3)) {
+            let (mint, taker_ata, maker_ata) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            let decimals = leg_mint_decimals(mint, &leg.mint, &token_program)?;
+            check_leg_ata(taker_ata, self.taker.key, &leg.mint, &token_program)?;
+            check_leg_ata(maker_ata, self.maker.key, &leg.mint, &token_program)?;
+            self.create_ata(maker_ata, &self.maker.to_account_info(), mint)?;
+
+            let transfer_accounts = TransferChecked {
+                from: taker_ata.clone(),
+                mint: mint.clone(),
+                to: maker_ata.clone(),
+                authority: self.taker.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+            transfer_checked(cpi_ctx, leg.amount, decimals)?;
+        }
+        Ok(())
+    }
+
+    fn release_vaults(&self, accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"basket",
+            self.maker.to_account_info().key.as_ref(),
+            &self.basket.seed.to_le_bytes()[..],
+            &[self.basket.bump],
+        ]];
+
+        let token_program = self.token_program.key();
+        for (leg, accounts) in self.basket.offered.iter().zip(accounts.chunks(3)) {
+            let (mint, vault, taker_ata) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            let decimals = leg_mint_decimals(mint, &leg.mint, &token_program)?;
+            check_leg_ata(vault, &self.basket.key(), &leg.mint, &token_program)?;
+            check_leg_ata(taker_ata, self.taker.key, &leg.mint, &token_program)?;
+            self.create_ata(taker_ata, &self.taker.to_account_info(), mint)?;
+
+            let transfer_accounts = TransferChecked {
+                from: vault.clone(),
+                mint: mint.clone(),
+                to: taker_ata.clone(),
+                authority: self.basket.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_accounts,
+                &signer_seeds,
+            );
+            transfer_checked(ctx, token_amount(vault)?, decimals)?;
+
+            let close_accounts = CloseAccount {
+                account: vault.clone(),
+                destination: self.maker.to_account_info(),
+                authority: self.basket.to_account_info(),
+            };
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                close_accounts,
+                &signer_seeds,
+            );
+            close_account(ctx)?;
+        }
+        Ok(())
+    }
+}
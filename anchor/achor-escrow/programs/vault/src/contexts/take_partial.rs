@@ -0,0 +1,231 @@
+use anchor_lang::{prelude::*, solana_program::sysvar::instructions};
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{
+    errors::EscrowError, events::OfferTaken, transfer::transfer_exact, Escrow, EscrowConfig,
+    OfferBook,
+};
+
+#[derive(Accounts)]
+pub struct TakePartial<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program
+    )]
+    pub taker_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program
+    )]
+    pub taker_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, EscrowConfig>,
+
+    #[account(address = config.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        has_one = mint_b,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book", escrow.mint_a.as_ref(), escrow.mint_b.as_ref()],
+        bump = offer_book.load()?.bump
+    )]
+    pub offer_book: AccountLoader<'info, OfferBook>,
+
+    /// CHECK: address is checked against the instructions sysvar
+    #[account(address = instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakePartial<'info> {
+    pub fn check_taker(&self) -> Result<()> {
+        self.escrow.check_taker(
+            &self.escrow.key(),
+            &self.taker.key(),
+            &self.instructions_sysvar,
+        )
+    }
+
+    /// Share of the vault released for `amount_b`, rounded down so the maker
+    /// never gives up more than the offered price. Filling the whole remaining
+    /// `receive` releases everything left in the vault.
+    pub fn fill_amount(&self, amount_b: u64) -> Result<u64> {
+        require!(
+            amount_b > 0 && amount_b <= self.escrow.receive,
+            EscrowError::InvalidFillAmount
+        );
+
+        let amount_a = (self.vault.amount as u128)
+            .checked_mul(amount_b as u128)
+            .ok_or(EscrowError::Overflow)?
+            .checked_div(self.escrow.receive as u128)
+            .ok_or(EscrowError::Overflow)? as u64;
+        require!(amount_a > 0, EscrowError::FillTooSmall);
+
+        Ok(amount_a)
+    }
+
+    pub fn deposit(&mut self, amount_b: u64) -> Result<()> {
+        let fee = self.config.fee_for(amount_b)?;
+
+        transfer_exact(
+            self.token_program.to_account_info(),
+            self.taker_ata_b.to_account_info(),
+            self.taker.to_account_info(),
+            &self.mint_b,
+            &mut self.maker_ata_b,
+            amount_b - fee,
+        )?;
+        transfer_exact(
+            self.token_program.to_account_info(),
+            self.taker_ata_b.to_account_info(),
+            self.taker.to_account_info(),
+            &self.mint_b,
+            &mut self.treasury_ata_b,
+            fee,
+        )?;
+
+        self.escrow.receive -= amount_b;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, amount_a: u64) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            self.maker.to_account_info().key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+            to: self.taker_ata_a.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        transfer_checked(ctx, amount_a, self.mint_a.decimals)
+    }
+
+    pub fn record_fill(&self, amount_a: u64, amount_b: u64) -> Result<()> {
+        emit!(OfferTaken {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            taker: self.taker.key(),
+            amount_a,
+            amount_b,
+            fee: self.config.fee_for(amount_b)?,
+        });
+        Ok(())
+    }
+
+    /// Updates the listed size, dropping the listing once filled.
+    pub fn sync_listing(&mut self) -> Result<()> {
+        self.vault.reload()?;
+        self.offer_book
+            .load_mut()?
+            .list(self.escrow.key(), &self.escrow, self.vault.amount);
+        Ok(())
+    }
+
+    /// Closes the vault and escrow once nothing is left to receive.
+    pub fn close_if_filled(&mut self) -> Result<()> {
+        if self.escrow.receive > 0 {
+            return Ok(());
+        }
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            self.maker.to_account_info().key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.taker.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            &signer_seeds,
+        );
+        close_account(ctx)?;
+
+        self.escrow.close(self.maker.to_account_info())
+    }
+}
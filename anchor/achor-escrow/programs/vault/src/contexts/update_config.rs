@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::EscrowConfig;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, EscrowConfig>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(
+        &mut self,
+        new_admin: Pubkey,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        EscrowConfig::validate_fee(fee_bps)?;
+
+        self.config.admin = new_admin;
+        self.config.fee_bps = fee_bps;
+        self.config.treasury = treasury;
+        Ok(())
+    }
+}
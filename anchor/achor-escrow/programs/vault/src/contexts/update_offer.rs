@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{errors::EscrowError, Escrow, OfferBook};
+
+#[derive(Accounts)]
+pub struct UpdateOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"book", escrow.mint_a.as_ref(), escrow.mint_b.as_ref()],
+        bump = offer_book.load()?.bump
+    )]
+    pub offer_book: AccountLoader<'info, OfferBook>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateOffer<'info> {
+    /// Reprices the offer and moves `additional_deposit` of `mint_a` into the
+    /// vault, or out of it when negative.
+    pub fn update_offer(&mut self, new_receive: u64, additional_deposit: i64) -> Result<()> {
+        require!(
+            new_receive > 0
+                && (additional_deposit >= 0
+                    || additional_deposit.unsigned_abs() < self.vault.amount),
+            EscrowError::InvalidOfferUpdate
+        );
+
+        match additional_deposit {
+            0 => {}
+            amount if amount > 0 => self.top_up(amount as u64)?,
+            amount => self.withdraw(amount.unsigned_abs())?,
+        }
+
+        self.escrow.receive = new_receive;
+        self.escrow.version = self
+            .escrow
+            .version
+            .checked_add(1)
+            .ok_or(EscrowError::Overflow)?;
+
+        self.vault.reload()?;
+        self.offer_book
+            .load_mut()?
+            .list(self.escrow.key(), &self.escrow, self.vault.amount);
+        Ok(())
+    }
+
+    fn top_up(&self, amount: u64) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.maker_ata_a.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint_a.decimals)
+    }
+
+    fn withdraw(&self, amount: u64) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            self.maker.to_account_info().key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let transfer_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+            to: self.maker_ata_a.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            transfer_accounts,
+            &signer_seeds,
+        );
+        transfer_checked(ctx, amount, self.mint_a.decimals)
+    }
+}
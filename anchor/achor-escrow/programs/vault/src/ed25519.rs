@@ -0,0 +1,79 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{ed25519_program, sysvar::instructions::load_instruction_at_checked},
+};
+
+use crate::errors::EscrowError;
+
+// Layout of the ed25519 program instruction data: a two byte header
+// (signature count, padding) followed by one offsets entry per signature.
+const HEADER_LEN: usize = 2;
+const OFFSETS_LEN: usize = 14;
+// Instruction index meaning "this instruction's own data".
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], at: usize) -> Result<u16> {
+    let bytes = data
+        .get(at..at + 2)
+        .ok_or(EscrowError::InvalidAuthorization)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_slice(data: &[u8], offset: u16, len: usize) -> Result<&[u8]> {
+    let start = offset as usize;
+    Ok(data
+        .get(start..start + len)
+        .ok_or(EscrowError::InvalidAuthorization)?)
+}
+
+/// Checks that the first instruction of the transaction is an ed25519
+/// verification of `message` signed by `signer`. The ed25519 program has
+/// already rejected the transaction if the signature itself is bad.
+pub fn verify_signed_message(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let ix = load_instruction_at_checked(0, instructions_sysvar)?;
+
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        EscrowError::InvalidAuthorization
+    );
+    require_eq!(ix.accounts.len(), 0, EscrowError::InvalidAuthorization);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= HEADER_LEN + OFFSETS_LEN && data[0] == 1,
+        EscrowError::InvalidAuthorization
+    );
+
+    let offsets = HEADER_LEN;
+    let signature_ix = read_u16(data, offsets + 2)?;
+    let public_key_offset = read_u16(data, offsets + 4)?;
+    let public_key_ix = read_u16(data, offsets + 6)?;
+    let message_offset = read_u16(data, offsets + 8)?;
+    let message_size = read_u16(data, offsets + 10)?;
+    let message_ix = read_u16(data, offsets + 12)?;
+
+    // Everything must live in this instruction, otherwise the verified bytes
+    // are not the ones read below.
+    require!(
+        signature_ix == CURRENT_INSTRUCTION
+            && public_key_ix == CURRENT_INSTRUCTION
+            && message_ix == CURRENT_INSTRUCTION,
+        EscrowError::InvalidAuthorization
+    );
+
+    let public_key = read_slice(data, public_key_offset, 32)?;
+    require!(
+        public_key == signer.as_ref(),
+        EscrowError::InvalidAuthorization
+    );
+
+    let signed_message = read_slice(data, message_offset, message_size as usize)?;
+    require!(signed_message == message, EscrowError::InvalidAuthorization);
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::error_code;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Fill amount must be between 1 and the remaining receive amount.")]
+    InvalidFillAmount,
+    #[msg("Fill is too small to release any tokens.")]
+    FillTooSmall,
+    #[msg("Expiry must be in the future.")]
+    InvalidExpiry,
+    #[msg("Offer has expired.")]
+    OfferExpired,
+    #[msg("Offer has not expired yet.")]
+    OfferNotExpired,
+    #[msg("Offer is reserved for a different taker.")]
+    TakerNotAllowed,
+    #[msg("Missing or invalid maker authorization for this taker.")]
+    InvalidAuthorization,
+    #[msg("Basket legs must be non-empty, within the leg limit, non-zero and use distinct mints.")]
+    InvalidBasket,
+    #[msg("Remaining accounts do not match the basket legs.")]
+    InvalidBasketAccounts,
+    #[msg("Offer was amended since the expected version.")]
+    VersionMismatch,
+    #[msg("Offer must keep a non-zero receive amount and vault balance.")]
+    InvalidOfferUpdate,
+    #[msg("Fee exceeds the maximum allowed.")]
+    InvalidFee,
+    #[msg("Only the program's upgrade authority can initialize the config.")]
+    NotUpgradeAuthority,
+    #[msg("Recipient was not credited the exact amount.")]
+    TransferFeeMismatch,
+    #[msg("Buyer, seller and arbiter must be distinct and the amount non-zero.")]
+    InvalidParties,
+    #[msg("Only the buyer or seller can do this.")]
+    NotParty,
+    #[msg("Only the arbiter can rule on a dispute.")]
+    NotArbiter,
+    #[msg("Escrow is already in dispute.")]
+    AlreadyDisputed,
+    #[msg("Escrow is not in dispute.")]
+    NotDisputed,
+    #[msg("Arbiter can still rule on this dispute.")]
+    RulingWindowOpen,
+    #[msg("Seller share exceeds the escrowed amount.")]
+    InvalidSplit,
+    #[msg("Tranches must be non-empty, within the limit, non-zero and due in the future.")]
+    InvalidTranches,
+    #[msg("No tranche at this index.")]
+    InvalidTranche,
+    #[msg("Tranche is not pending.")]
+    TrancheNotPending,
+    #[msg("Tranche deadline has passed.")]
+    TrancheDeadlinePassed,
+    #[msg("Tranche deadline has not been reached.")]
+    TrancheDeadlineNotReached,
+    #[msg("Overflow detected.")]
+    Overflow,
+}
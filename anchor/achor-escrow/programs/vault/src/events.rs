@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct OfferCreated {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub deposit: u64,
+    pub receive: u64,
+    pub expires_at: i64,
+}
+
+/// Emitted for full and partial takes; `amount_b` includes `fee`.
+#[event]
+pub struct OfferTaken {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct OfferRefunded {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::errors::EscrowError;
+
+// Helpers for basket legs passed through `remaining_accounts`, which get none
+// of the checks Anchor applies to typed accounts.
+
+/// Checks a leg's mint account and returns its decimals.
+pub fn leg_mint_decimals(
+    mint: &AccountInfo,
+    expected: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<u8> {
+    require_keys_eq!(*mint.key, *expected, EscrowError::InvalidBasketAccounts);
+    require_keys_eq!(
+        *mint.owner,
+        *token_program,
+        EscrowError::InvalidBasketAccounts
+    );
+    let data = mint.try_borrow_data()?;
+    Ok(Mint::try_deserialize(&mut &data[..])?.decimals)
+}
+
+/// Checks that `account` is the associated token account of `owner` for `mint`.
+pub fn check_leg_ata(
+    account: &AccountInfo,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    let expected = get_associated_token_address_with_program_id(owner, mint, token_program);
+    require_keys_eq!(*account.key, expected, EscrowError::InvalidBasketAccounts);
+    Ok(())
+}
+
+pub fn token_amount(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+}
@@ -2,7 +2,13 @@ use anchor_lang::prelude::*;
 
 declare_id!("AjgeKU3JYAHkJVScSQdNwF5tyDC86cykdyjnr2Lw4sam");
 
+pub mod constants;
+pub mod ed25519;
+pub mod errors;
+pub mod events;
+pub mod legs;
 pub mod state;
+pub mod transfer;
 pub use state::*;
 pub mod contexts;
 pub use contexts::*;
@@ -11,16 +17,171 @@ pub use contexts::*;
 pub mod escrow {
     use super::*;
 
-    pub fn make(ctx: Context<Make>, seed: u64, deposit: u64, receive: u64) -> Result<()> {
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_config(fee_bps, treasury, &ctx.bumps)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        new_admin: Pubkey,
+        fee_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.update_config(new_admin, fee_bps, treasury)
+    }
+
+    pub fn init_offer_book(ctx: Context<InitOfferBook>) -> Result<()> {
+        ctx.accounts.init_offer_book(&ctx.bumps)
+    }
+
+    pub fn make(
+        ctx: Context<Make>,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expires_at: i64,
+        taker: Option<Pubkey>,
+        require_authorization: bool,
+    ) -> Result<()> {
         ctx.accounts.deposit(deposit)?;
-        ctx.accounts.save_escrow(seed, receive, &ctx.bumps)
+        ctx.accounts.save_escrow(
+            seed,
+            receive,
+            expires_at,
+            taker,
+            require_authorization,
+            &ctx.bumps,
+        )?;
+        ctx.accounts.list_offer(&ctx.bumps)
     }
-    pub fn take(ctx: Context<Take>) -> Result<()> {
+    pub fn take(ctx: Context<Take>, expected_version: Option<u64>) -> Result<()> {
+        ctx.accounts.escrow.check_not_expired()?;
+        ctx.accounts.escrow.check_version(expected_version)?;
+        ctx.accounts.check_taker()?;
         ctx.accounts.deposit()?;
+        ctx.accounts.delist()?;
         ctx.accounts.withdraw_and_close_vault()
     }
 
+    pub fn take_partial(
+        ctx: Context<TakePartial>,
+        amount_b: u64,
+        expected_version: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.escrow.check_not_expired()?;
+        ctx.accounts.escrow.check_version(expected_version)?;
+        ctx.accounts.check_taker()?;
+        let amount_a = ctx.accounts.fill_amount(amount_b)?;
+        ctx.accounts.deposit(amount_b)?;
+        ctx.accounts.withdraw(amount_a)?;
+        ctx.accounts.record_fill(amount_a, amount_b)?;
+        ctx.accounts.sync_listing()?;
+        ctx.accounts.close_if_filled()
+    }
+
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        ctx.accounts.delist()?;
         ctx.accounts.refund_and_close_vault()
     }
+
+    pub fn update_offer(
+        ctx: Context<UpdateOffer>,
+        new_receive: u64,
+        additional_deposit: i64,
+    ) -> Result<()> {
+        ctx.accounts.update_offer(new_receive, additional_deposit)
+    }
+
+    pub fn crank_refund(ctx: Context<CrankRefund>) -> Result<()> {
+        ctx.accounts.escrow.check_expired()?;
+        ctx.accounts.delist()?;
+        ctx.accounts.refund_and_close_vault()
+    }
+
+    pub fn open_service(
+        ctx: Context<OpenService>,
+        seed: u64,
+        seller: Pubkey,
+        arbiter: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .save_service(seed, seller, arbiter, amount, &ctx.bumps)?;
+        ctx.accounts.deposit(amount)
+    }
+
+    pub fn release(ctx: Context<SettleService>) -> Result<()> {
+        ctx.accounts.release()
+    }
+
+    pub fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
+        ctx.accounts.raise_dispute()
+    }
+
+    pub fn rule(ctx: Context<SettleService>, seller_amount: u64) -> Result<()> {
+        ctx.accounts.rule(seller_amount)
+    }
+
+    pub fn claim_default(ctx: Context<SettleService>) -> Result<()> {
+        ctx.accounts.claim_default()
+    }
+
+    pub fn create_milestones(
+        ctx: Context<CreateMilestones>,
+        seed: u64,
+        payee: Pubkey,
+        arbiter: Pubkey,
+        tranches: Vec<TrancheInput>,
+    ) -> Result<()> {
+        let total = ctx
+            .accounts
+            .save_milestones(seed, payee, arbiter, tranches, &ctx.bumps)?;
+        ctx.accounts.deposit(total)
+    }
+
+    pub fn dispute_tranche(ctx: Context<DisputeTranche>, index: u8) -> Result<()> {
+        ctx.accounts.dispute_tranche(index)
+    }
+
+    pub fn approve_tranche(ctx: Context<SettleTranche>, index: u8) -> Result<()> {
+        ctx.accounts.approve_tranche(index)
+    }
+
+    pub fn claim_tranche(ctx: Context<SettleTranche>, index: u8) -> Result<()> {
+        ctx.accounts.claim_tranche(index)
+    }
+
+    pub fn refund_tranche(ctx: Context<SettleTranche>, index: u8) -> Result<()> {
+        ctx.accounts.refund_tranche(index)
+    }
+
+    pub fn rule_tranche(ctx: Context<SettleTranche>, index: u8, release: bool) -> Result<()> {
+        ctx.accounts.rule_tranche(index, release)
+    }
+
+    pub fn make_basket<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeBasket<'info>>,
+        seed: u64,
+        offered: Vec<BasketLeg>,
+        requested: Vec<BasketLeg>,
+    ) -> Result<()> {
+        ctx.accounts
+            .save_basket(seed, offered, requested, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)
+    }
+
+    pub fn take_basket<'info>(ctx: Context<'_, '_, '_, 'info, TakeBasket<'info>>) -> Result<()> {
+        ctx.accounts.settle(ctx.remaining_accounts)
+    }
+
+    pub fn refund_basket<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundBasket<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.refund_and_close_vaults(ctx.remaining_accounts)
+    }
 }
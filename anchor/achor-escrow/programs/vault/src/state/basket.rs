@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::MAX_BASKET_LEGS, errors::EscrowError};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Bundle swap: every `offered` leg sits in its own vault owned by this
+/// account and is released only if all `requested` legs are paid.
+#[account]
+#[derive(InitSpace)]
+pub struct Basket {
+    pub seed: u64,
+    pub maker: Pubkey,
+    #[max_len(MAX_BASKET_LEGS)]
+    pub offered: Vec<BasketLeg>,
+    #[max_len(MAX_BASKET_LEGS)]
+    pub requested: Vec<BasketLeg>,
+    pub bump: u8,
+}
+
+impl Basket {
+    pub fn validate_legs(legs: &[BasketLeg]) -> Result<()> {
+        require!(
+            !legs.is_empty() && legs.len() <= MAX_BASKET_LEGS,
+            EscrowError::InvalidBasket
+        );
+        for (i, leg) in legs.iter().enumerate() {
+            require!(leg.amount > 0, EscrowError::InvalidBasket);
+            require!(
+                legs[..i].iter().all(|other| other.mint != leg.mint),
+                EscrowError::InvalidBasket
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{ed25519::verify_signed_message, errors::EscrowError};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Escrow {
@@ -7,7 +9,57 @@ pub struct Escrow {
     pub maker: Pubkey,
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
+    /// Amount of `mint_b` still wanted for what is left in the vault.
     pub receive: u64,
+    /// Unix timestamp after which the offer can no longer be taken.
+    pub expires_at: i64,
+    /// Only this key may take the offer when set.
+    pub taker: Option<Pubkey>,
+    /// When set (and no `taker` is designated), takers must present the
+    /// maker's ed25519 signature over `escrow || taker`.
+    pub require_authorization: bool,
+    /// Bumped on every `update_offer` so takers can detect amendments.
+    pub version: u64,
     pub bump: u8,
 }
 
+impl Escrow {
+    pub fn check_not_expired(&self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < self.expires_at, EscrowError::OfferExpired);
+        Ok(())
+    }
+
+    pub fn check_version(&self, expected_version: Option<u64>) -> Result<()> {
+        if let Some(expected) = expected_version {
+            require_eq!(self.version, expected, EscrowError::VersionMismatch);
+        }
+        Ok(())
+    }
+
+    /// Open to any taker without the maker's say-so, so it can be listed.
+    pub fn is_public(&self) -> bool {
+        self.taker.is_none() && !self.require_authorization
+    }
+
+    pub fn check_taker(
+        &self,
+        escrow: &Pubkey,
+        taker: &Pubkey,
+        instructions_sysvar: &AccountInfo,
+    ) -> Result<()> {
+        if let Some(designated) = self.taker {
+            require_keys_eq!(*taker, designated, EscrowError::TakerNotAllowed);
+        } else if self.require_authorization {
+            let message = [escrow.as_ref(), taker.as_ref()].concat();
+            verify_signed_message(instructions_sysvar, &self.maker, &message)?;
+        }
+        Ok(())
+    }
+
+    pub fn check_expired(&self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.expires_at, EscrowError::OfferNotExpired);
+        Ok(())
+    }
+}
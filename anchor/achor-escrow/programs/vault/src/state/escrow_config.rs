@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::MAX_FEE_BPS, errors::EscrowError};
+
+/// Program-wide settings for the protocol fee taken from `mint_b` on takes.
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowConfig {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub bump: u8,
+}
+
+impl EscrowConfig {
+    pub fn validate_fee(fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= MAX_FEE_BPS, EscrowError::InvalidFee);
+        Ok(())
+    }
+
+    /// Protocol share of `amount`, rounded up so splitting a take into many
+    /// small fills can't dodge the fee.
+    pub fn fee_for(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(self.fee_bps as u128)
+            .ok_or(EscrowError::Overflow)?
+            .div_ceil(10_000);
+        Ok(fee as u64)
+    }
+}
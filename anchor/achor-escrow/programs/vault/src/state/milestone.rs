@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DISPUTE_RULING_TIMEOUT, MAX_TRANCHES},
+    errors::EscrowError,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TrancheStatus {
+    Pending,
+    /// Maker objected before the deadline. Only the arbiter can settle it
+    /// until `ruling_deadline`, after which it can be claimed like a pending one.
+    Disputed {
+        ruling_deadline: i64,
+    },
+    Released,
+    Refunded,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TrancheInput {
+    pub amount: u64,
+    pub description_hash: [u8; 32],
+    pub deadline: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct Tranche {
+    pub amount: u64,
+    /// Hash of the off-chain description of the deliverable.
+    pub description_hash: [u8; 32],
+    /// Payee can claim a still pending tranche from this time on.
+    pub deadline: i64,
+    pub status: TrancheStatus,
+}
+
+/// Staged payment from `maker` to `payee`, funded up front and paid out
+/// tranche by tranche.
+#[account]
+#[derive(InitSpace)]
+pub struct Milestone {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub payee: Pubkey,
+    pub arbiter: Pubkey,
+    pub mint: Pubkey,
+    #[max_len(MAX_TRANCHES)]
+    pub tranches: Vec<Tranche>,
+    pub released: u64,
+    pub refunded: u64,
+    pub bump: u8,
+}
+
+impl Milestone {
+    /// Validates the requested tranches and returns their total amount.
+    pub fn build_tranches(inputs: Vec<TrancheInput>) -> Result<(Vec<Tranche>, u64)> {
+        require!(
+            !inputs.is_empty() && inputs.len() <= MAX_TRANCHES,
+            EscrowError::InvalidTranches
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut total: u64 = 0;
+        let mut tranches = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            require!(
+                input.amount > 0 && input.deadline > now,
+                EscrowError::InvalidTranches
+            );
+            total = total
+                .checked_add(input.amount)
+                .ok_or(EscrowError::Overflow)?;
+            tranches.push(Tranche {
+                amount: input.amount,
+                description_hash: input.description_hash,
+                deadline: input.deadline,
+                status: TrancheStatus::Pending,
+            });
+        }
+        Ok((tranches, total))
+    }
+
+    pub fn tranche(&self, index: u8) -> Result<&Tranche> {
+        self.tranches
+            .get(index as usize)
+            .ok_or(error!(EscrowError::InvalidTranche))
+    }
+
+    pub fn tranche_mut(&mut self, index: u8) -> Result<&mut Tranche> {
+        self.tranches
+            .get_mut(index as usize)
+            .ok_or(error!(EscrowError::InvalidTranche))
+    }
+
+    pub fn dispute(&mut self, index: u8) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let tranche = self.tranche_mut(index)?;
+        require!(
+            tranche.status == TrancheStatus::Pending,
+            EscrowError::TrancheNotPending
+        );
+        require!(now < tranche.deadline, EscrowError::TrancheDeadlinePassed);
+
+        let ruling_deadline = now
+            .checked_add(DISPUTE_RULING_TIMEOUT)
+            .ok_or(EscrowError::Overflow)?;
+        tranche.status = TrancheStatus::Disputed { ruling_deadline };
+        Ok(())
+    }
+
+    /// A pending tranche can be claimed from its deadline on, and a disputed
+    /// one once the arbiter has let the ruling deadline pass.
+    pub fn check_claimable(&self, index: u8) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let tranche = self.tranche(index)?;
+        match tranche.status {
+            TrancheStatus::Pending => require!(
+                now >= tranche.deadline,
+                EscrowError::TrancheDeadlineNotReached
+            ),
+            TrancheStatus::Disputed { ruling_deadline } => {
+                require!(now >= ruling_deadline, EscrowError::RulingWindowOpen)
+            }
+            _ => return err!(EscrowError::TrancheNotPending),
+        }
+        Ok(())
+    }
+
+    pub fn check_ruling(&self, index: u8, caller: &Pubkey) -> Result<()> {
+        require_keys_eq!(*caller, self.arbiter, EscrowError::NotArbiter);
+        require!(
+            matches!(self.tranche(index)?.status, TrancheStatus::Disputed { .. }),
+            EscrowError::NotDisputed
+        );
+        Ok(())
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.tranches
+            .iter()
+            .filter(|t| {
+                matches!(
+                    t.status,
+                    TrancheStatus::Pending | TrancheStatus::Disputed { .. }
+                )
+            })
+            .map(|t| t.amount)
+            .sum()
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.tranches
+            .iter()
+            .all(|t| matches!(t.status, TrancheStatus::Released | TrancheStatus::Refunded))
+    }
+}
@@ -1,2 +1,17 @@
 pub mod escrow;
-pub use escrow::*;
\ No newline at end of file
+pub use escrow::*;
+
+pub mod basket;
+pub use basket::*;
+
+pub mod escrow_config;
+pub use escrow_config::*;
+
+pub mod service_escrow;
+pub use service_escrow::*;
+
+pub mod milestone;
+pub use milestone::*;
+
+pub mod offer_book;
+pub use offer_book::*;
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::OFFER_BOOK_CAPACITY, Escrow};
+
+#[zero_copy]
+pub struct OfferEntry {
+    pub escrow: Pubkey,
+    /// `mint_b` still asked for the whole `size`.
+    pub receive: u64,
+    /// `mint_a` left in the escrow vault.
+    pub size: u64,
+}
+
+impl OfferEntry {
+    /// Whether this entry asks no more per unit of `mint_a` than
+    /// `receive / size`, compared exactly by cross-multiplying.
+    fn is_cheaper_or_equal(&self, receive: u64, size: u64) -> bool {
+        self.receive as u128 * size as u128 <= receive as u128 * self.size as u128
+    }
+}
+
+/// Open offers for one `(mint_a, mint_b)` pair, cheapest first, so clients
+/// can read the best prices in a single account fetch.
+#[account(zero_copy)]
+pub struct OfferBook {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub len: u64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub offers: [OfferEntry; OFFER_BOOK_CAPACITY],
+}
+
+impl OfferBook {
+    pub fn entries(&self) -> &[OfferEntry] {
+        &self.offers[..self.len as usize]
+    }
+
+    /// Lists `escrow` at its current terms, replacing any earlier listing.
+    /// Private offers and offers with nothing left on either side stay out
+    /// of the book.
+    pub fn list(&mut self, key: Pubkey, escrow: &Escrow, size: u64) {
+        self.remove(&key);
+        if escrow.is_public() && escrow.receive > 0 && size > 0 {
+            self.insert(key, escrow.receive, size);
+        }
+    }
+
+    /// Inserts after any offers at the same price. A full book drops its most
+    /// expensive offer to make room, or leaves the new one out if that offer
+    /// is no worse.
+    fn insert(&mut self, escrow: Pubkey, receive: u64, size: u64) {
+        let len = self.len as usize;
+        let pos = self
+            .entries()
+            .partition_point(|o| o.is_cheaper_or_equal(receive, size));
+
+        if len == OFFER_BOOK_CAPACITY {
+            if pos == len {
+                return;
+            }
+            self.offers.copy_within(pos..len - 1, pos + 1);
+        } else {
+            self.offers.copy_within(pos..len, pos + 1);
+            self.len += 1;
+        }
+
+        self.offers[pos] = OfferEntry {
+            escrow,
+            receive,
+            size,
+        };
+    }
+
+    /// Removes `escrow` if it is listed; offers dropped from a full book are
+    /// simply not found.
+    pub fn remove(&mut self, escrow: &Pubkey) {
+        let len = self.len as usize;
+        if let Some(pos) = self.entries().iter().position(|o| o.escrow == *escrow) {
+            self.offers.copy_within(pos + 1..len, pos);
+            self.offers[len - 1] = OfferEntry {
+                escrow: Pubkey::default(),
+                receive: 0,
+                size: 0,
+            };
+            self.len -= 1;
+        }
+    }
+}
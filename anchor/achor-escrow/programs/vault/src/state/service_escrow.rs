@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::DISPUTE_RULING_TIMEOUT, errors::EscrowError};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ServiceStatus {
+    Funded,
+    /// Only the arbiter can settle, until `ruling_deadline` passes.
+    Disputed {
+        ruling_deadline: i64,
+    },
+}
+
+/// Release-on-delivery escrow for off-chain work: the buyer's funds sit in
+/// the vault until the buyer releases them or the arbiter rules on a dispute.
+#[account]
+#[derive(InitSpace)]
+pub struct ServiceEscrow {
+    pub seed: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub arbiter: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub status: ServiceStatus,
+    pub bump: u8,
+}
+
+impl ServiceEscrow {
+    pub fn raise_dispute(&mut self, caller: &Pubkey) -> Result<()> {
+        require!(
+            *caller == self.buyer || *caller == self.seller,
+            EscrowError::NotParty
+        );
+        require!(
+            self.status == ServiceStatus::Funded,
+            EscrowError::AlreadyDisputed
+        );
+
+        let ruling_deadline = Clock::get()?
+            .unix_timestamp
+            .checked_add(DISPUTE_RULING_TIMEOUT)
+            .ok_or(EscrowError::Overflow)?;
+        self.status = ServiceStatus::Disputed { ruling_deadline };
+        Ok(())
+    }
+
+    pub fn check_release(&self, caller: &Pubkey) -> Result<()> {
+        require_keys_eq!(*caller, self.buyer, EscrowError::NotParty);
+        require!(
+            self.status == ServiceStatus::Funded,
+            EscrowError::AlreadyDisputed
+        );
+        Ok(())
+    }
+
+    pub fn check_ruling(&self, caller: &Pubkey) -> Result<()> {
+        require_keys_eq!(*caller, self.arbiter, EscrowError::NotArbiter);
+        require!(
+            matches!(self.status, ServiceStatus::Disputed { .. }),
+            EscrowError::NotDisputed
+        );
+        Ok(())
+    }
+
+    pub fn check_default(&self) -> Result<()> {
+        let ServiceStatus::Disputed { ruling_deadline } = self.status else {
+            return err!(EscrowError::NotDisputed);
+        };
+        require!(
+            Clock::get()?.unix_timestamp >= ruling_deadline,
+            EscrowError::RulingWindowOpen
+        );
+        Ok(())
+    }
+}
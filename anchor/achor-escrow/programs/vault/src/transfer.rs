@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Mint as MintState,
+    },
+    token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked},
+};
+
+use crate::errors::EscrowError;
+
+/// Amount to send so that `net` arrives after any Token-2022 transfer fee.
+pub fn gross_amount(mint: &InterfaceAccount<Mint>, net: u64) -> Result<u64> {
+    let info = mint.to_account_info();
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    let Ok(fee_config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(net);
+    };
+    let fee = fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, net)
+        .ok_or(EscrowError::Overflow)?;
+    Ok(net.checked_add(fee).ok_or(EscrowError::Overflow)?)
+}
+
+/// Transfers so that `to` is credited with exactly `net`, paying any transfer
+/// fee on top, and fails if the credited amount comes out different.
+pub fn transfer_exact<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    net: u64,
+) -> Result<()> {
+    if net == 0 {
+        return Ok(());
+    }
+
+    let before = to.amount;
+    let transfer_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority,
+    };
+    let cpi_ctx = CpiContext::new(token_program, transfer_accounts);
+    transfer_checked(cpi_ctx, gross_amount(mint, net)?, mint.decimals)?;
+
+    to.reload()?;
+    require_eq!(
+        to.amount.checked_sub(before).ok_or(EscrowError::Overflow)?,
+        net,
+        EscrowError::TransferFeeMismatch
+    );
+    Ok(())
+}
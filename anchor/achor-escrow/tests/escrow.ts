@@ -0,0 +1,1345 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import {
+  Ed25519Program,
+  LAMPORTS_PER_SOL,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  MINT_SIZE,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+  unpackAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+import { readFileSync } from "fs";
+import { Escrow } from "../target/types/escrow";
+import IDL from "../target/idl/escrow.json";
+
+const { BN } = anchor;
+
+// Advance one slot so a repeated transaction gets a fresh blockhash instead
+// of being rejected as already processed.
+const nextSlot = async (context: ProgramTestContext) => {
+  const clock = await context.banksClient.getClock();
+  context.warpToSlot(clock.slot + BigInt(1));
+};
+
+// Move the bank clock to `unixTimestamp`, keeping everything else as is.
+const warpTo = async (context: ProgramTestContext, unixTimestamp: number) => {
+  await nextSlot(context);
+  const clock = await context.banksClient.getClock();
+  context.setClock(
+    new Clock(
+      clock.slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+};
+
+const now = async (context: ProgramTestContext) =>
+  Number((await context.banksClient.getClock()).unixTimestamp);
+
+const expectError = async (promise: Promise<unknown>, code: string) => {
+  let error: unknown;
+  try {
+    await promise;
+  } catch (err) {
+    error = err;
+  }
+  assert.isDefined(error, `expected ${code}`);
+  assert.include(String(error), code);
+};
+
+const BPF_LOADER_UPGRADEABLE = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+// Bankrun loads programs as immutable, so re-register the escrow under the
+// upgradeable loader with `authority` as the upgrade authority the config
+// initialization checks.
+const setUpgradeAuthority = async (
+  context: ProgramTestContext,
+  programId: PublicKey,
+  authority: PublicKey
+) => {
+  const [programData] = PublicKey.findProgramAddressSync(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE
+  );
+  const tag = (value: number) => {
+    const buffer = Buffer.alloc(4);
+    buffer.writeUInt32LE(value);
+    return buffer;
+  };
+  const rent = await context.banksClient.getRent();
+  const account = (data: Buffer, executable: boolean) => ({
+    lamports: Number(rent.minimumBalance(BigInt(data.length))),
+    data,
+    owner: BPF_LOADER_UPGRADEABLE,
+    executable,
+  });
+
+  context.setAccount(
+    programData,
+    account(
+      Buffer.concat([
+        tag(3), // UpgradeableLoaderState::ProgramData
+        Buffer.alloc(8), // deployment slot
+        Buffer.from([1]),
+        authority.toBuffer(),
+        readFileSync("target/deploy/escrow.so"),
+      ]),
+      false
+    )
+  );
+  context.setAccount(
+    programId,
+    account(Buffer.concat([tag(2), programData.toBuffer()]), true)
+  );
+  return programData;
+};
+
+const seedBytes = (seed: number) => new BN(seed).toArrayLike(Buffer, "le", 8);
+
+const findEscrow = (programId: PublicKey, maker: PublicKey, seed: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("escrow"), maker.toBuffer(), seedBytes(seed)],
+    programId
+  )[0];
+
+const ata = (
+  mint: PublicKey,
+  owner: PublicKey,
+  tokenProgram = TOKEN_PROGRAM_ID
+) => getAssociatedTokenAddressSync(mint, owner, true, tokenProgram);
+
+const findBasket = (programId: PublicKey, maker: PublicKey, seed: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("basket"), maker.toBuffer(), seedBytes(seed)],
+    programId
+  )[0];
+
+const findBook = (programId: PublicKey, mintA: PublicKey, mintB: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("book"), mintA.toBuffer(), mintB.toBuffer()],
+    programId
+  )[0];
+
+const findService = (programId: PublicKey, buyer: PublicKey, seed: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("service"), buyer.toBuffer(), seedBytes(seed)],
+    programId
+  )[0];
+
+const findMilestone = (programId: PublicKey, maker: PublicKey, seed: number) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("milestone"), maker.toBuffer(), seedBytes(seed)],
+    programId
+  )[0];
+
+const writable = (pubkey: PublicKey) => ({
+  pubkey,
+  isSigner: false,
+  isWritable: true,
+});
+
+const readonly = (pubkey: PublicKey) => ({
+  pubkey,
+  isSigner: false,
+  isWritable: false,
+});
+
+describe("escrow", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<Escrow>;
+  let maker: PublicKey;
+  const taker = Keypair.generate();
+  const treasury = Keypair.generate().publicKey;
+  let mintA: PublicKey;
+  let mintB: PublicKey;
+
+  const createMint = async () => {
+    const mint = Keypair.generate();
+    const rent = await context.banksClient.getRent();
+    const tx = new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: maker,
+        newAccountPubkey: mint.publicKey,
+        space: MINT_SIZE,
+        lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMint2Instruction(mint.publicKey, 6, maker, null)
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  };
+
+  const mintTo = async (
+    mint: PublicKey,
+    owner: PublicKey,
+    amount: number,
+    tokenProgram = TOKEN_PROGRAM_ID
+  ) => {
+    const account = ata(mint, owner, tokenProgram);
+    const tx = new Transaction().add(
+      createAssociatedTokenAccountIdempotentInstruction(
+        maker,
+        account,
+        owner,
+        mint,
+        tokenProgram
+      ),
+      createMintToInstruction(mint, account, maker, amount, [], tokenProgram)
+    );
+    await provider.sendAndConfirm(tx);
+  };
+
+  const balance = async (account: PublicKey) => {
+    const info = await context.banksClient.getAccount(account);
+    if (!info) return BigInt(0);
+    return unpackAccount(
+      account,
+      { ...info, data: Buffer.from(info.data) },
+      info.owner
+    ).amount;
+  };
+
+  const initOfferBook = (a: PublicKey, b: PublicKey) =>
+    program.methods
+      .initOfferBook()
+      .accountsPartial({ payer: maker, mintA: a, mintB: b })
+      .rpc();
+
+  const updateConfig = (feeBps: number) =>
+    program.methods
+      .updateConfig(maker, feeBps, treasury)
+      .accountsPartial({ admin: maker })
+      .rpc();
+
+  const DAY = 60 * 60 * 24;
+
+  type MakeOptions = {
+    expiresAt?: number;
+    taker?: PublicKey;
+    requireAuthorization?: boolean;
+  };
+
+  const make = async (
+    seed: number,
+    deposit: number,
+    receive: number,
+    options: MakeOptions = {}
+  ) => {
+    const expiresAt = options.expiresAt ?? (await now(context)) + DAY;
+    await program.methods
+      .make(
+        new BN(seed),
+        new BN(deposit),
+        new BN(receive),
+        new BN(expiresAt),
+        options.taker ?? null,
+        options.requireAuthorization ?? false
+      )
+      .accountsPartial({
+        maker,
+        mintA,
+        mintB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    return findEscrow(program.programId, maker, seed);
+  };
+
+  const takePartial = (
+    escrow: PublicKey,
+    amountB: number,
+    expectedVersion: number | null = null
+  ) =>
+    program.methods
+      .takePartial(
+        new BN(amountB),
+        expectedVersion === null ? null : new BN(expectedVersion)
+      )
+      .accountsPartial({
+        taker: taker.publicKey,
+        maker,
+        mintA,
+        mintB,
+        escrow,
+        treasury,
+        offerBook: findBook(program.programId, mintA, mintB),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+
+  const take = (
+    escrow: PublicKey,
+    signer: Keypair = taker,
+    preInstructions: anchor.web3.TransactionInstruction[] = []
+  ) =>
+    program.methods
+      .take(null)
+      .accountsPartial({
+        taker: signer.publicKey,
+        maker,
+        mintA,
+        mintB,
+        escrow,
+        treasury,
+        offerBook: findBook(program.programId, mintA, mintB),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .preInstructions(preInstructions)
+      .signers([signer])
+      .rpc();
+
+  let programData: PublicKey;
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<Escrow>(IDL as Escrow, provider);
+    maker = provider.wallet.publicKey;
+    programData = await setUpgradeAuthority(context, program.programId, maker);
+
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: maker,
+          toPubkey: taker.publicKey,
+          lamports: 10 * LAMPORTS_PER_SOL,
+        })
+      )
+    );
+
+    mintA = await createMint();
+    mintB = await createMint();
+    await mintTo(mintA, maker, 1_000_000);
+    await mintTo(mintB, taker.publicKey, 1_000_000);
+    await initOfferBook(mintA, mintB);
+  });
+
+  // Mocha runs this before the nested suites, which all need the config.
+  it("Only lets the upgrade authority initialize the config", async () => {
+    await expectError(
+      program.methods
+        .initializeConfig(0, taker.publicKey)
+        .accountsPartial({ admin: taker.publicKey, programData })
+        .signers([taker])
+        .rpc(),
+      "NotUpgradeAuthority"
+    );
+
+    await program.methods
+      .initializeConfig(0, treasury)
+      .accountsPartial({ admin: maker, programData })
+      .rpc();
+  });
+
+  describe("partial fills", () => {
+    let escrow: PublicKey;
+
+    before(async () => {
+      // 1_000 A offered for 300 B.
+      escrow = await make(1, 1_000, 300);
+    });
+
+    it("Releases a proportional share rounded down", async () => {
+      await takePartial(escrow, 100);
+
+      assert.equal(await balance(ata(mintA, taker.publicKey)), BigInt(333));
+      assert.equal(await balance(ata(mintB, maker)), BigInt(100));
+      assert.equal(await balance(ata(mintA, escrow)), BigInt(667));
+
+      const state = await program.account.escrow.fetch(escrow);
+      assert.equal(state.receive.toNumber(), 200);
+    });
+
+    it("Rejects fills above the remaining amount", async () => {
+      await expectError(takePartial(escrow, 201), "InvalidFillAmount");
+    });
+
+    it("Rejects empty fills", async () => {
+      await expectError(takePartial(escrow, 0), "InvalidFillAmount");
+    });
+
+    it("Closes the escrow and vault once fully filled", async () => {
+      await takePartial(escrow, 200);
+
+      assert.equal(await balance(ata(mintA, taker.publicKey)), BigInt(1_000));
+      assert.equal(await balance(ata(mintB, maker)), BigInt(300));
+      assert.isNull(await context.banksClient.getAccount(escrow));
+      assert.isNull(await context.banksClient.getAccount(ata(mintA, escrow)));
+    });
+  });
+
+  describe("small fills", () => {
+    it("Rejects fills that would release nothing", async () => {
+      const escrow = await make(2, 10, 1_000);
+      await expectError(takePartial(escrow, 5), "FillTooSmall");
+    });
+  });
+
+  describe("amendments", () => {
+    let escrow: PublicKey;
+
+    const updateOffer = (newReceive: number, additionalDeposit: number) =>
+      program.methods
+        .updateOffer(new BN(newReceive), new BN(additionalDeposit))
+        .accountsPartial({
+          maker,
+          mintA,
+          escrow,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      escrow = await make(5, 100, 10);
+    });
+
+    it("Tops up the vault and reprices the offer", async () => {
+      await updateOffer(20, 50);
+
+      const state = await program.account.escrow.fetch(escrow);
+      assert.equal(state.receive.toNumber(), 20);
+      assert.equal(state.version.toNumber(), 1);
+      assert.equal(await balance(ata(mintA, escrow)), BigInt(150));
+    });
+
+    it("Partially withdraws from the vault", async () => {
+      await updateOffer(15, -30);
+
+      const state = await program.account.escrow.fetch(escrow);
+      assert.equal(state.receive.toNumber(), 15);
+      assert.equal(state.version.toNumber(), 2);
+      assert.equal(await balance(ata(mintA, escrow)), BigInt(120));
+    });
+
+    it("Rejects emptying the vault", async () => {
+      await expectError(updateOffer(15, -120), "InvalidOfferUpdate");
+    });
+
+    it("Rejects takes against a stale version", async () => {
+      await expectError(takePartial(escrow, 5, 1), "VersionMismatch");
+      await takePartial(escrow, 5, 2);
+    });
+  });
+
+  describe("protocol fee", () => {
+    after(async () => {
+      await updateConfig(0);
+    });
+
+    it("Rejects fees above the maximum", async () => {
+      await expectError(updateConfig(1_001), "InvalidFee");
+    });
+
+    it("Only lets the admin update the config", async () => {
+      await expectError(
+        program.methods
+          .updateConfig(taker.publicKey, 0, taker.publicKey)
+          .accountsPartial({ admin: taker.publicKey })
+          .signers([taker])
+          .rpc(),
+        "ConstraintHasOne"
+      );
+    });
+
+    it("Splits the payment between treasury and maker", async () => {
+      await updateConfig(250);
+      const escrow = await make(6, 100, 1_000);
+      const makerB = await balance(ata(mintB, maker));
+
+      await take(escrow);
+
+      assert.equal((await balance(ata(mintB, maker))) - makerB, BigInt(975));
+      assert.equal(await balance(ata(mintB, treasury)), BigInt(25));
+    });
+
+    it("Charges the fee on every small fill", async () => {
+      await updateConfig(100);
+      const escrow = await make(8, 100, 1_000);
+      const makerB = await balance(ata(mintB, maker));
+      const treasuryB = await balance(ata(mintB, treasury));
+
+      // 1% of 10 rounds up to 1 on each fill.
+      for (let i = 0; i < 5; i++) {
+        await takePartial(escrow, 10);
+      }
+
+      assert.equal((await balance(ata(mintB, maker))) - makerB, BigInt(45));
+      assert.equal(
+        (await balance(ata(mintB, treasury))) - treasuryB,
+        BigInt(5)
+      );
+      await updateConfig(250);
+    });
+
+    it("Delivers exact net amounts for transfer-fee mints", async () => {
+      const tokenProgram = TOKEN_2022_PROGRAM_ID;
+      const rent = await context.banksClient.getRent();
+
+      const plainMint = Keypair.generate();
+      const feeMint = Keypair.generate();
+      const feeMintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: maker,
+            newAccountPubkey: plainMint.publicKey,
+            space: MINT_SIZE,
+            lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+            programId: tokenProgram,
+          }),
+          createInitializeMint2Instruction(
+            plainMint.publicKey,
+            6,
+            maker,
+            null,
+            tokenProgram
+          ),
+          SystemProgram.createAccount({
+            fromPubkey: maker,
+            newAccountPubkey: feeMint.publicKey,
+            space: feeMintLen,
+            lamports: Number(rent.minimumBalance(BigInt(feeMintLen))),
+            programId: tokenProgram,
+          }),
+          // 1% transfer fee.
+          createInitializeTransferFeeConfigInstruction(
+            feeMint.publicKey,
+            maker,
+            maker,
+            100,
+            BigInt(1_000_000),
+            tokenProgram
+          ),
+          createInitializeMint2Instruction(
+            feeMint.publicKey,
+            6,
+            maker,
+            null,
+            tokenProgram
+          )
+        ),
+        [plainMint, feeMint]
+      );
+      const [a, b] = [plainMint.publicKey, feeMint.publicKey];
+      await mintTo(a, maker, 1_000, tokenProgram);
+      await mintTo(b, taker.publicKey, 10_000, tokenProgram);
+
+      await program.methods
+        .make(
+          new BN(7),
+          new BN(100),
+          new BN(1_000),
+          new BN((await now(context)) + DAY),
+          null,
+          false
+        )
+        .accountsPartial({ maker, mintA: a, mintB: b, tokenProgram })
+        .rpc();
+      const escrow = findEscrow(program.programId, maker, 7);
+
+      await program.methods
+        .take(null)
+        .accountsPartial({
+          taker: taker.publicKey,
+          maker,
+          mintA: a,
+          mintB: b,
+          escrow,
+          treasury,
+          tokenProgram,
+        })
+        .signers([taker])
+        .rpc();
+
+      assert.equal(await balance(ata(b, maker, tokenProgram)), BigInt(975));
+      assert.equal(await balance(ata(b, treasury, tokenProgram)), BigInt(25));
+      assert.isBelow(
+        Number(await balance(ata(b, taker.publicKey, tokenProgram))),
+        9_000
+      );
+    });
+  });
+
+  describe("private offers", () => {
+    const outsider = Keypair.generate();
+
+    // Maker's ed25519 authorization for `signer` to take `escrow`.
+    const authorize = (escrow: PublicKey, signer: PublicKey, by = context.payer) =>
+      Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: by.secretKey,
+        message: Buffer.concat([escrow.toBuffer(), signer.toBuffer()]),
+      });
+
+    before(async () => {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: maker,
+            toPubkey: outsider.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        )
+      );
+      await mintTo(mintB, outsider.publicKey, 1_000);
+    });
+
+    it("Only lets the designated taker take", async () => {
+      const escrow = await make(10, 100, 10, { taker: taker.publicKey });
+
+      await expectError(take(escrow, outsider), "TakerNotAllowed");
+      await take(escrow);
+      assert.isNull(await context.banksClient.getAccount(escrow));
+    });
+
+    it("Applies the designated taker to partial fills", async () => {
+      const escrow = await make(11, 100, 10, { taker: outsider.publicKey });
+      await expectError(takePartial(escrow, 5), "TakerNotAllowed");
+    });
+
+    it("Requires the maker's authorization when asked to", async () => {
+      const escrow = await make(12, 100, 10, { requireAuthorization: true });
+
+      await expectError(take(escrow, outsider), "InvalidAuthorization");
+      await expectError(
+        take(escrow, outsider, [authorize(escrow, taker.publicKey)]),
+        "InvalidAuthorization"
+      );
+      await expectError(
+        take(escrow, outsider, [authorize(escrow, outsider.publicKey, outsider)]),
+        "InvalidAuthorization"
+      );
+
+      await take(escrow, outsider, [authorize(escrow, outsider.publicKey)]);
+      assert.isNull(await context.banksClient.getAccount(escrow));
+    });
+  });
+
+  describe("offer book", () => {
+    let book: PublicKey;
+    let cheap: PublicKey;
+    let mid: PublicKey;
+    let dear: PublicKey;
+
+    const refund = (escrow: PublicKey) =>
+      program.methods
+        .refund()
+        .accountsPartial({
+          maker,
+          mintA,
+          escrow,
+          offerBook: book,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    const isListed = async (escrow: PublicKey) =>
+      (await listed()).some((o) => o.escrow.equals(escrow));
+
+    const listed = async () => {
+      const state = await program.account.offerBook.fetch(book);
+      return state.offers.slice(0, state.len.toNumber());
+    };
+
+    before(async () => {
+      book = findBook(program.programId, mintA, mintB);
+      // Clear out whatever earlier tests left listed.
+      for (const { escrow } of await listed()) {
+        await refund(escrow);
+      }
+
+      mid = await make(50, 100, 200);
+      dear = await make(51, 100, 300);
+      cheap = await make(52, 100, 100);
+    });
+
+    it("Lists offers cheapest first", async () => {
+      const offers = await listed();
+      assert.deepEqual(
+        offers.map((o) => o.escrow.toBase58()),
+        [cheap, mid, dear].map((k) => k.toBase58())
+      );
+      assert.equal(offers[0].receive.toNumber(), 100);
+      assert.equal(offers[0].size.toNumber(), 100);
+    });
+
+    it("Shrinks a listing on partial fills", async () => {
+      await takePartial(mid, 100);
+
+      const offers = await listed();
+      assert.equal(offers[1].escrow.toBase58(), mid.toBase58());
+      assert.equal(offers[1].size.toNumber(), 50);
+    });
+
+    it("Removes offers when taken or refunded", async () => {
+      await take(cheap);
+      await refund(dear);
+
+      const offers = await listed();
+      assert.deepEqual(
+        offers.map((o) => o.escrow.toBase58()),
+        [mid.toBase58()]
+      );
+    });
+
+    it("Removes a refunded offer from the book", async () => {
+      const escrow = await make(58, 100, 100);
+      assert.isTrue(await isListed(escrow));
+
+      await refund(escrow);
+      assert.isFalse(await isListed(escrow));
+    });
+
+    it("Keeps private offers out of the book", async () => {
+      const designated = await make(53, 100, 10, { taker: taker.publicKey });
+      const authorized = await make(54, 100, 10, {
+        requireAuthorization: true,
+      });
+
+      assert.isFalse(await isListed(designated));
+      assert.isFalse(await isListed(authorized));
+
+      await refund(designated);
+      await refund(authorized);
+    });
+
+    it("Keeps offers asking nothing out of the book", async () => {
+      const free = await make(55, 100, 0);
+      assert.isFalse(await isListed(free));
+      await refund(free);
+    });
+
+    it("Skips listing in a full book unless the offer is cheaper", async () => {
+      // Fill the book up behind `mid` (2 B per A) with offers at 0.1 B per A.
+      for (let seed = 100; (await listed()).length < 64; seed++) {
+        await make(seed, 100, 10);
+      }
+
+      const pricier = await make(56, 100, 1_000);
+      assert.isFalse(await isListed(pricier));
+
+      const cheaper = await make(57, 100, 5);
+      const offers = await listed();
+      assert.equal(offers.length, 64);
+      assert.equal(offers[0].escrow.toBase58(), cheaper.toBase58());
+      assert.isFalse(await isListed(mid));
+    });
+  });
+
+  describe("events", () => {
+    // Sends `tx` straight to the bank and decodes the events from its logs.
+    const sendForEvents = async (tx: Transaction, signers: Keypair[] = []) => {
+      tx.recentBlockhash = context.lastBlockhash;
+      tx.feePayer = context.payer.publicKey;
+      tx.sign(context.payer, ...signers);
+
+      const meta = await context.banksClient.processTransaction(tx);
+      const parser = new anchor.EventParser(program.programId, program.coder);
+      return [...parser.parseLogs(meta.logMessages)];
+    };
+
+    const makeTx = async (seed: number) =>
+      program.methods
+        .make(
+          new BN(seed),
+          new BN(100),
+          new BN(40),
+          new BN((await now(context)) + DAY),
+          null,
+          false
+        )
+        .accountsPartial({
+          maker,
+          mintA,
+          mintB,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .transaction();
+
+    it("Emits OfferCreated on make", async () => {
+      const [event] = await sendForEvents(await makeTx(60));
+
+      assert.equal(event.name, "offerCreated");
+      assert.equal(
+        event.data.escrow.toBase58(),
+        findEscrow(program.programId, maker, 60).toBase58()
+      );
+      assert.equal(event.data.deposit.toNumber(), 100);
+      assert.equal(event.data.receive.toNumber(), 40);
+    });
+
+    it("Emits OfferTaken on partial and full takes", async () => {
+      const escrow = findEscrow(program.programId, maker, 60);
+      const takeTx = (amountB: number) =>
+        program.methods
+          .takePartial(new BN(amountB), null)
+          .accountsPartial({
+            taker: taker.publicKey,
+            maker,
+            mintA,
+            mintB,
+            escrow,
+            treasury,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .transaction();
+
+      const [partial] = await sendForEvents(await takeTx(10), [taker]);
+      assert.equal(partial.name, "offerTaken");
+      assert.equal(partial.data.taker.toBase58(), taker.publicKey.toBase58());
+      assert.equal(partial.data.amountA.toNumber(), 25);
+      assert.equal(partial.data.amountB.toNumber(), 10);
+
+      const [full] = await sendForEvents(await takeTx(30), [taker]);
+      assert.equal(full.data.amountA.toNumber(), 75);
+    });
+
+    it("Emits OfferRefunded on refund", async () => {
+      await sendForEvents(await makeTx(61));
+      const escrow = findEscrow(program.programId, maker, 61);
+
+      const [event] = await sendForEvents(
+        await program.methods
+          .refund()
+          .accountsPartial({
+            maker,
+            mintA,
+            escrow,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .transaction()
+      );
+
+      assert.equal(event.name, "offerRefunded");
+      assert.equal(event.data.amount.toNumber(), 100);
+    });
+  });
+
+  describe("baskets", () => {
+    let mintC: PublicKey;
+
+    type Leg = { mint: PublicKey; amount: number };
+    const legs = (list: Leg[]) =>
+      list.map(({ mint, amount }) => ({ mint, amount: new BN(amount) }));
+
+    const makeBasket = async (seed: number, offered: Leg[], requested: Leg[]) => {
+      const basket = findBasket(program.programId, maker, seed);
+      await program.methods
+        .makeBasket(new BN(seed), legs(offered), legs(requested))
+        .accountsPartial({ maker, basket, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(
+          offered.flatMap(({ mint }) => [
+            readonly(mint),
+            writable(ata(mint, maker)),
+            writable(ata(mint, basket)),
+          ])
+        )
+        .rpc();
+      return basket;
+    };
+
+    const takeAccounts = (basket: PublicKey, offered: Leg[], requested: Leg[]) => [
+      ...offered.flatMap(({ mint }) => [
+        readonly(mint),
+        writable(ata(mint, basket)),
+        writable(ata(mint, taker.publicKey)),
+      ]),
+      ...requested.flatMap(({ mint }) => [
+        readonly(mint),
+        writable(ata(mint, taker.publicKey)),
+        writable(ata(mint, maker)),
+      ]),
+    ];
+
+    const takeBasket = (basket: PublicKey, remaining: ReturnType<typeof writable>[]) =>
+      program.methods
+        .takeBasket()
+        .accountsPartial({
+          taker: taker.publicKey,
+          maker,
+          basket,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(remaining)
+        .signers([taker])
+        .rpc();
+
+    before(async () => {
+      mintC = await createMint();
+      await mintTo(mintC, maker, 1_000);
+    });
+
+    it("Rejects duplicate mints in a leg list", async () => {
+      await expectError(
+        makeBasket(
+          20,
+          [
+            { mint: mintA, amount: 1 },
+            { mint: mintA, amount: 1 },
+          ],
+          [{ mint: mintB, amount: 1 }]
+        ),
+        "InvalidBasket"
+      );
+    });
+
+    it("Settles every leg of a basket in one take", async () => {
+      const offered = [
+        { mint: mintA, amount: 100 },
+        { mint: mintC, amount: 50 },
+      ];
+      const requested = [{ mint: mintB, amount: 30 }];
+      const basket = await makeBasket(21, offered, requested);
+
+      assert.equal(await balance(ata(mintA, basket)), BigInt(100));
+      assert.equal(await balance(ata(mintC, basket)), BigInt(50));
+
+      const takerA = await balance(ata(mintA, taker.publicKey));
+      const makerB = await balance(ata(mintB, maker));
+
+      await expectError(
+        takeBasket(basket, takeAccounts(basket, offered, [])),
+        "InvalidBasketAccounts"
+      );
+      await takeBasket(basket, takeAccounts(basket, offered, requested));
+
+      assert.equal(
+        (await balance(ata(mintA, taker.publicKey))) - takerA,
+        BigInt(100)
+      );
+      assert.equal(await balance(ata(mintC, taker.publicKey)), BigInt(50));
+      assert.equal((await balance(ata(mintB, maker))) - makerB, BigInt(30));
+      assert.isNull(await context.banksClient.getAccount(basket));
+      assert.isNull(await context.banksClient.getAccount(ata(mintA, basket)));
+      assert.isNull(await context.banksClient.getAccount(ata(mintC, basket)));
+    });
+
+    it("Returns every leg to the maker on refund", async () => {
+      const offered = [
+        { mint: mintA, amount: 10 },
+        { mint: mintC, amount: 5 },
+      ];
+      const basket = await makeBasket(22, offered, [{ mint: mintB, amount: 1 }]);
+      const makerC = await balance(ata(mintC, maker));
+
+      await program.methods
+        .refundBasket()
+        .accountsPartial({ maker, basket, tokenProgram: TOKEN_PROGRAM_ID })
+        .remainingAccounts(
+          offered.flatMap(({ mint }) => [
+            readonly(mint),
+            writable(ata(mint, basket)),
+            writable(ata(mint, maker)),
+          ])
+        )
+        .rpc();
+
+      assert.equal((await balance(ata(mintC, maker))) - makerC, BigInt(5));
+      assert.isNull(await context.banksClient.getAccount(basket));
+    });
+  });
+
+  describe("service escrow", () => {
+    const arbiter = Keypair.generate();
+    const seller = taker;
+
+    const openService = async (seed: number, amount: number) => {
+      await program.methods
+        .openService(
+          new BN(seed),
+          seller.publicKey,
+          arbiter.publicKey,
+          new BN(amount)
+        )
+        .accountsPartial({
+          buyer: maker,
+          mint: mintA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      return findService(program.programId, maker, seed);
+    };
+
+    const settle = (
+      method: "release" | "claimDefault",
+      service: PublicKey,
+      caller?: Keypair
+    ) => {
+      const builder = program.methods[method]().accountsPartial({
+        caller: caller?.publicKey ?? maker,
+        buyer: maker,
+        seller: seller.publicKey,
+        mint: mintA,
+        service,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      });
+      return caller ? builder.signers([caller]).rpc() : builder.rpc();
+    };
+
+    const rule = (service: PublicKey, sellerAmount: number, caller: Keypair) =>
+      program.methods
+        .rule(new BN(sellerAmount))
+        .accountsPartial({
+          caller: caller.publicKey,
+          buyer: maker,
+          seller: seller.publicKey,
+          mint: mintA,
+          service,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([caller])
+        .rpc();
+
+    const raiseDispute = (service: PublicKey, caller: Keypair) =>
+      program.methods
+        .raiseDispute()
+        .accountsPartial({ caller: caller.publicKey, service })
+        .signers([caller])
+        .rpc();
+
+    before(async () => {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: maker,
+            toPubkey: arbiter.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        )
+      );
+    });
+
+    it("Rejects the buyer as their own arbiter", async () => {
+      await expectError(
+        program.methods
+          .openService(new BN(30), seller.publicKey, maker, new BN(10))
+          .accountsPartial({
+            buyer: maker,
+            mint: mintA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc(),
+        "InvalidParties"
+      );
+    });
+
+    it("Lets the buyer release to the seller", async () => {
+      const service = await openService(31, 100);
+      const sellerA = await balance(ata(mintA, seller.publicKey));
+
+      await expectError(settle("release", service, seller), "NotParty");
+      await settle("release", service);
+
+      assert.equal(
+        (await balance(ata(mintA, seller.publicKey))) - sellerA,
+        BigInt(100)
+      );
+      assert.isNull(await context.banksClient.getAccount(service));
+    });
+
+    it("Hands a dispute over to the arbiter", async () => {
+      const service = await openService(32, 100);
+      const sellerA = await balance(ata(mintA, seller.publicKey));
+      const buyerA = await balance(ata(mintA, maker));
+
+      await expectError(raiseDispute(service, arbiter), "NotParty");
+      await raiseDispute(service, seller);
+      await expectError(settle("release", service), "AlreadyDisputed");
+      await expectError(rule(service, 30, seller), "NotArbiter");
+      await expectError(rule(service, 101, arbiter), "InvalidSplit");
+
+      await rule(service, 30, arbiter);
+
+      assert.equal(
+        (await balance(ata(mintA, seller.publicKey))) - sellerA,
+        BigInt(30)
+      );
+      assert.equal((await balance(ata(mintA, maker))) - buyerA, BigInt(70));
+      assert.isNull(await context.banksClient.getAccount(service));
+    });
+
+    it("Defaults to the seller when the arbiter stays silent", async () => {
+      const service = await openService(33, 100);
+      const sellerA = await balance(ata(mintA, seller.publicKey));
+
+      await expectError(settle("claimDefault", service), "NotDisputed");
+      await raiseDispute(service, context.payer);
+      await expectError(settle("claimDefault", service), "RulingWindowOpen");
+
+      await warpTo(context, (await now(context)) + 14 * DAY);
+      await settle("claimDefault", service, seller);
+
+      assert.equal(
+        (await balance(ata(mintA, seller.publicKey))) - sellerA,
+        BigInt(100)
+      );
+    });
+  });
+
+  describe("milestones", () => {
+    const payee = taker;
+    const arbiter = Keypair.generate();
+    let milestone: PublicKey;
+    let start: number;
+
+    const settleAccounts = (caller: Keypair) => ({
+      caller: caller.publicKey,
+      maker,
+      payee: payee.publicKey,
+      mint: mintA,
+      milestone,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    const settle = (
+      method: "approveTranche" | "claimTranche" | "refundTranche",
+      index: number,
+      caller: Keypair
+    ) =>
+      program.methods[method](index)
+        .accountsPartial(settleAccounts(caller))
+        .signers([caller])
+        .rpc();
+
+    const rule = (index: number, release: boolean, caller: Keypair) =>
+      program.methods
+        .ruleTranche(index, release)
+        .accountsPartial(settleAccounts(caller))
+        .signers([caller])
+        .rpc();
+
+    const createMilestones = async (seed: number, amounts: number[]) => {
+      const tranches = amounts.map((amount, i) => ({
+        amount: new BN(amount),
+        descriptionHash: Array(32).fill(i),
+        deadline: new BN(start + (i + 1) * DAY),
+      }));
+      await program.methods
+        .createMilestones(
+          new BN(seed),
+          payee.publicKey,
+          arbiter.publicKey,
+          tranches
+        )
+        .accountsPartial({ maker, mint: mintA, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+      return findMilestone(program.programId, maker, seed);
+    };
+
+    before(async () => {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: maker,
+            toPubkey: arbiter.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        )
+      );
+
+      start = await now(context);
+      milestone = await createMilestones(40, [100, 200, 300]);
+    });
+
+    it("Rejects the maker as their own arbiter", async () => {
+      await expectError(
+        program.methods
+          .createMilestones(new BN(39), payee.publicKey, maker, [
+            {
+              amount: new BN(100),
+              descriptionHash: Array(32).fill(0),
+              deadline: new BN(start + DAY),
+            },
+          ])
+          .accountsPartial({
+            maker,
+            mint: mintA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc(),
+        "InvalidParties"
+      );
+    });
+
+    it("Funds the vault with every tranche", async () => {
+      assert.equal(await balance(ata(mintA, milestone)), BigInt(600));
+    });
+
+    it("Releases a tranche when the maker approves it", async () => {
+      const payeeA = await balance(ata(mintA, payee.publicKey));
+
+      await expectError(settle("approveTranche", 0, payee), "NotParty");
+      await settle("approveTranche", 0, context.payer);
+
+      assert.equal(
+        (await balance(ata(mintA, payee.publicKey))) - payeeA,
+        BigInt(100)
+      );
+      const state = await program.account.milestone.fetch(milestone);
+      assert.equal(state.released.toNumber(), 100);
+    });
+
+    it("Holds a disputed tranche past its deadline", async () => {
+      await program.methods
+        .disputeTranche(1)
+        .accountsPartial({ maker, milestone })
+        .rpc();
+      await warpTo(context, start + 2 * DAY);
+
+      await expectError(settle("claimTranche", 1, payee), "RulingWindowOpen");
+    });
+
+    it("Refunds a tranche the payee gives up", async () => {
+      const makerA = await balance(ata(mintA, maker));
+
+      await expectError(
+        settle("refundTranche", 1, context.payer),
+        "NotParty"
+      );
+      await settle("refundTranche", 1, payee);
+
+      assert.equal((await balance(ata(mintA, maker))) - makerA, BigInt(200));
+      const state = await program.account.milestone.fetch(milestone);
+      assert.equal(state.refunded.toNumber(), 200);
+    });
+
+    it("Releases a pending tranche at its deadline and closes", async () => {
+      await expectError(
+        settle("claimTranche", 2, payee),
+        "TrancheDeadlineNotReached"
+      );
+      await warpTo(context, start + 3 * DAY);
+
+      const payeeA = await balance(ata(mintA, payee.publicKey));
+      await settle("claimTranche", 2, payee);
+
+      assert.equal(
+        (await balance(ata(mintA, payee.publicKey))) - payeeA,
+        BigInt(300)
+      );
+      assert.isNull(await context.banksClient.getAccount(milestone));
+      assert.isNull(await context.banksClient.getAccount(ata(mintA, milestone)));
+    });
+
+    it("Lets the arbiter rule on a disputed tranche", async () => {
+      start = await now(context);
+      milestone = await createMilestones(41, [100, 200]);
+      const makerA = await balance(ata(mintA, maker));
+
+      await expectError(rule(0, false, arbiter), "NotDisputed");
+      await program.methods
+        .disputeTranche(0)
+        .accountsPartial({ maker, milestone })
+        .rpc();
+      await expectError(rule(0, false, payee), "NotArbiter");
+      await rule(0, false, arbiter);
+
+      assert.equal((await balance(ata(mintA, maker))) - makerA, BigInt(100));
+    });
+
+    it("Releases a disputed tranche once the arbiter stays silent", async () => {
+      await program.methods
+        .disputeTranche(1)
+        .accountsPartial({ maker, milestone })
+        .rpc();
+      await warpTo(context, (await now(context)) + 14 * DAY);
+
+      const payeeA = await balance(ata(mintA, payee.publicKey));
+      await settle("claimTranche", 1, payee);
+
+      assert.equal(
+        (await balance(ata(mintA, payee.publicKey))) - payeeA,
+        BigInt(200)
+      );
+      assert.isNull(await context.banksClient.getAccount(milestone));
+    });
+  });
+
+  describe("expiry", () => {
+    const cranker = Keypair.generate();
+    let escrow: PublicKey;
+    let expiresAt: number;
+
+    const crankRefund = () =>
+      program.methods
+        .crankRefund()
+        .accountsPartial({
+          caller: cranker.publicKey,
+          maker,
+          mintA,
+          escrow,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([cranker])
+        .rpc();
+
+    before(async () => {
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: maker,
+            toPubkey: cranker.publicKey,
+            lamports: LAMPORTS_PER_SOL,
+          })
+        )
+      );
+      expiresAt = (await now(context)) + DAY;
+      escrow = await make(3, 500, 50, { expiresAt });
+    });
+
+    it("Rejects an expiry in the past", async () => {
+      await expectError(
+        make(4, 500, 50, { expiresAt: (await now(context)) - 1 }),
+        "InvalidExpiry"
+      );
+    });
+
+    it("Rejects crank refunds before expiry", async () => {
+      await expectError(crankRefund(), "OfferNotExpired");
+    });
+
+    it("Rejects takes after expiry", async () => {
+      await warpTo(context, expiresAt);
+      await expectError(takePartial(escrow, 10), "OfferExpired");
+    });
+
+    it("Lets anyone refund the maker after expiry", async () => {
+      const makerAtaA = ata(mintA, maker);
+      const before = await balance(makerAtaA);
+      const makerLamports = await context.banksClient.getBalance(maker);
+
+      await crankRefund();
+
+      assert.equal((await balance(makerAtaA)) - before, BigInt(500));
+      assert.isNull(await context.banksClient.getAccount(escrow));
+      assert.isNull(await context.banksClient.getAccount(ata(mintA, escrow)));
+      assert.isAbove(
+        Number(await context.banksClient.getBalance(maker)),
+        Number(makerLamports)
+      );
+    });
+  });
+});
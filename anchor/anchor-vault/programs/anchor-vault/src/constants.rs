@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+/// Seconds a proposed withdraw limit or guardian change must wait before it can be applied.
+pub const CONFIG_CHANGE_DELAY: i64 = 60 * 60 * 24 * 2;
+
+/// Window over which withdrawals that skip guardian approval are added up, so a
+/// large withdrawal cannot be split into several just under the threshold.
+pub const APPROVAL_WINDOW: i64 = 60 * 60 * 24;
+
+/// Maximum number of guardians that can co-sign withdrawals from a vault.
+pub const MAX_GUARDIANS: usize = 10;
+
+/// Maximum number of vaults a single user can keep in their `VaultIndex`.
+pub const MAX_VAULTS_PER_USER: usize = 16;
+
+/// Shortest inactivity timeout a beneficiary can be given, so a stolen key
+/// cannot name itself beneficiary and claim the vault right away.
+pub const MIN_INACTIVITY_TIMEOUT: i64 = 60 * 60 * 24 * 30;
+
+/// Maximum number of stake accounts a vault can have open at once.
+pub const MAX_STAKE_ACCOUNTS: usize = 8;
+
+/// Stake config account the stake program still expects on `DelegateStake`.
+pub const STAKE_CONFIG_ID: Pubkey = pubkey!("StakeConfig11111111111111111111111111111111");
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, VaultState, WithdrawalProposal};
+
+#[derive(Accounts)]
+pub struct ApproveWithdrawal<'info> {
+    pub guardian: Signer<'info>,
+
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        has_one = vault_state,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, WithdrawalProposal>,
+}
+
+impl<'info> ApproveWithdrawal<'info> {
+    pub fn approve_withdrawal(&mut self) -> Result<()> {
+        let guardian = self.guardian.key();
+
+        require!(self.vault_state.guardians.contains(&guardian), VaultError::NotGuardian);
+        require!(!self.proposal.approvals.contains(&guardian), VaultError::AlreadyApproved);
+
+        // Approvals from guardians removed since they signed no longer count, and
+        // dropping them keeps the list within the space allocated for it.
+        let guardians = &self.vault_state.guardians;
+        self.proposal.approvals.retain(|approver| guardians.contains(approver));
+        self.proposal.approvals.push(guardian);
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{Stream, VaultState};
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = user,
+        has_one = vault_state,
+        has_one = recipient,
+        seeds = [b"stream", vault_state.key().as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+impl<'info> CancelStream<'info> {
+    /// Pays the recipient whatever has vested but not been withdrawn, and
+    /// returns the unvested rest to the vault.
+    pub fn cancel_stream(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let (owed, unvested) = self.stream.split_remaining(now)?;
+
+        self.stream.sub_lamports(owed)?;
+        self.recipient.add_lamports(owed)?;
+
+        self.stream.sub_lamports(unvested)?;
+        self.vault.add_lamports(unvested)?;
+
+        self.vault_state.record_stream_cancelled(unvested)?;
+        self.vault_state.record_heartbeat()
+    }
+}
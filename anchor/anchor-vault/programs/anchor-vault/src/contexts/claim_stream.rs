@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{Stream, VaultState};
+
+#[derive(Accounts)]
+pub struct ClaimStream<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    pub user: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = beneficiary,
+        has_one = vault_state,
+        has_one = recipient,
+        seeds = [b"stream", vault_state.key().as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+impl<'info> ClaimStream<'info> {
+    /// Cancels a stream on behalf of an inactive owner so `claim` is no longer
+    /// blocked by it. The recipient keeps what has vested, and the unvested rest
+    /// goes back into the vault for the beneficiary to claim.
+    pub fn claim_stream(&mut self) -> Result<()> {
+        self.vault_state.check_claimable(&self.beneficiary.key())?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let (owed, unvested) = self.stream.split_remaining(now)?;
+
+        self.stream.sub_lamports(owed)?;
+        self.recipient.add_lamports(owed)?;
+
+        self.stream.sub_lamports(unvested)?;
+        self.vault.add_lamports(unvested)?;
+
+        self.vault_state.record_stream_cancelled(unvested)
+    }
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+
+use crate::{errors::VaultError, events::Closed, VaultIndex, VaultState};
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump,
+        close = user
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [b"index", user.key().as_ref()],
+        bump = vault_index.bump
+    )]
+    pub vault_index: Account<'info, VaultIndex>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Close<'info> {
+    pub fn close(&mut self) -> Result<()> {
+        require_eq!(self.vault_state.active_streams, 0, VaultError::StreamsOutstanding);
+        require!(self.vault_state.stake_accounts.is_empty(), VaultError::StakeAccountsOutstanding);
+        self.vault_state.record_unapproved(self.vault.lamports())?;
+        self.vault_state.check_unlocked()?;
+
+        let amount = self.vault.lamports();
+        self.vault_state.record_withdrawal(amount)?;
+
+        let cpi_program = self.system_program.to_account_info();
+
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info(),
+        };
+
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        transfer(cpi_ctx, amount)?;
+
+        self.vault_index.remove(self.vault_state.vault_id);
+
+        emit!(Closed {
+            vault_state: self.vault_state.key(),
+            to: self.user.key(),
+            amount,
+            timestamp: self.vault_state.last_activity,
+        });
+        Ok(())
+    }
+}
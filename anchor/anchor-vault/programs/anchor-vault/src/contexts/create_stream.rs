@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+
+use crate::{errors::VaultError, events::Withdrawn, Stream, VaultState};
+
+#[derive(Accounts)]
+pub struct CreateStream<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        init,
+        payer = user,
+        space = 8 + Stream::INIT_SPACE,
+        seeds = [b"stream", vault_state.key().as_ref(), vault_state.stream_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateStream<'info> {
+    pub fn create_stream(
+        &mut self,
+        recipient: Pubkey,
+        total: u64,
+        start: i64,
+        end: i64,
+        cliff: i64,
+        bumps: &CreateStreamBumps,
+    ) -> Result<()> {
+        Stream::validate_schedule(total, start, end, cliff)?;
+
+        // Funding a stream moves lamports out of the vault, so it is held to the same rules as a withdrawal.
+        self.vault_state.record_unapproved(total)?;
+        self.vault_state.check_unlocked()?;
+        self.vault_state.record_withdrawal(total)?;
+        self.vault_state.record_heartbeat()?;
+
+        self.stream.set_inner(Stream {
+            vault_state: self.vault_state.key(),
+            recipient,
+            id: self.vault_state.stream_count,
+            total,
+            withdrawn: 0,
+            start,
+            end,
+            cliff,
+            bump: bumps.stream,
+        });
+
+        self.vault_state.stream_count = self.vault_state.stream_count.checked_add(1).ok_or(VaultError::Overflow)?;
+        self.vault_state.active_streams = self.vault_state.active_streams.checked_add(1).ok_or(VaultError::Overflow)?;
+
+        self.fund_stream(total)?;
+
+        emit!(Withdrawn {
+            vault_state: self.vault_state.key(),
+            to: self.stream.key(),
+            amount: total,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            timestamp: self.vault_state.last_activity,
+        });
+        Ok(())
+    }
+
+    fn fund_stream(&self, total: u64) -> Result<()> {
+        let cpi_program = self.system_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.stream.to_account_info(),
+        };
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, total)
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    program::invoke_signed,
+    stake::{self, instruction as stake_instruction},
+};
+
+use crate::VaultState;
+
+#[derive(Accounts)]
+pub struct DeactivateStake<'info> {
+    /// The owner, or anyone once the owner has been inactive for the timeout.
+    pub authority: Signer<'info>,
+
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    /// CHECK: must be one of the stake accounts tracked in `vault_state`
+    #[account(mut)]
+    pub stake_account: UncheckedAccount<'info>,
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: native stake program
+    #[account(address = stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+impl<'info> DeactivateStake<'info> {
+    pub fn deactivate_stake(&mut self) -> Result<()> {
+        self.vault_state.check_stake_account(self.stake_account.key)?;
+        if self.authority.key() != self.user.key() {
+            self.vault_state.check_owner_inactive()?;
+        }
+
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        invoke_signed(
+            &stake_instruction::deactivate_stake(self.stake_account.key, self.vault.key),
+            &[
+                self.stake_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.vault.to_account_info(),
+                self.stake_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        Ok(())
+    }
+}
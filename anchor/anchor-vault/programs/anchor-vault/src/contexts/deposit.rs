@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+
+use crate::{events::Deposited, VaultState};
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+
+}
+
+impl<'info> Deposit<'info> {
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        self.vault_state.record_heartbeat()?;
+        self.vault_state.record_deposit(amount)?;
+
+        let cpi_program: AccountInfo<'info> = self.system_program.to_account_info();
+        
+        let cpi_accounts: Transfer<'info> = Transfer {
+            from: self.user.to_account_info(),
+            to: self.vault.to_account_info()
+        };
+
+        let cpi_ctx: CpiContext<'_, '_, '_, 'info, Transfer<'info>> = CpiContext::new(cpi_program, cpi_accounts);
+        transfer(cpi_ctx, amount)?;
+
+        emit!(Deposited {
+            vault_state: self.vault_state.key(),
+            amount,
+            total_deposited: self.vault_state.total_deposited,
+            timestamp: self.vault_state.last_activity,
+        });
+        Ok(())
+    }
+}
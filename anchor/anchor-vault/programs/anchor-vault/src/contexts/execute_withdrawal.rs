@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+
+use crate::{errors::VaultError, events::Withdrawn, VaultState, WithdrawalProposal};
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawal<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        close = user,
+        has_one = vault_state,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, WithdrawalProposal>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteWithdrawal<'info> {
+    pub fn execute_withdrawal(&mut self) -> Result<()> {
+        require!(self.vault_state.is_approved(&self.proposal), VaultError::InsufficientApprovals);
+        self.vault_state.check_unlocked()?;
+        self.vault_state.record_withdrawal(self.proposal.amount)?;
+        self.vault_state.record_heartbeat()?;
+
+        let cpi_program = self.system_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info(),
+        };
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, self.proposal.amount)?;
+
+        emit!(Withdrawn {
+            vault_state: self.vault_state.key(),
+            to: self.user.key(),
+            amount: self.proposal.amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            timestamp: self.vault_state.last_activity,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, VaultState};
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> ExtendLock<'info> {
+    pub fn extend_lock(&mut self, unlock_at: i64) -> Result<()> {
+        require!(unlock_at > self.vault_state.unlock_at, VaultError::LockCannotBeShortened);
+
+        self.vault_state.unlock_at = unlock_at;
+        Ok(())
+    }
+}
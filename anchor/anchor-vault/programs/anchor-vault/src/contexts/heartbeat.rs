@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::VaultState;
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> Heartbeat<'info> {
+    pub fn heartbeat(&mut self) -> Result<()> {
+        self.vault_state.record_heartbeat()
+    }
+}
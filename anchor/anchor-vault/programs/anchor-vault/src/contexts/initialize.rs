@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{VaultIndex, VaultState};
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct Initialize <'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"state", user.key().as_ref(), vault_id.to_le_bytes().as_ref()], 
+        bump,
+        space = 8 + VaultState::INIT_SPACE,
+    )]
+
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"index", user.key().as_ref()],
+        bump,
+        space = 8 + VaultIndex::INIT_SPACE,
+    )]
+    pub vault_index: Account<'info, VaultIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Initialize<'info> {
+    pub fn initialize(
+        &mut self,
+        vault_id: u64,
+        unlock_at: i64,
+        max_withdraw_per_period: Option<u64>,
+        period_seconds: i64,
+        bumps: &InitializeBumps,
+    ) -> Result<()> {
+        VaultState::validate_limit(max_withdraw_per_period, period_seconds)?;
+
+        self.vault_state.set_inner(VaultState {
+            vault_bump: bumps.vault,
+            state_bump: bumps.vault_state,
+            unlock_at,
+            max_withdraw_per_period,
+            period_seconds,
+            period_start: Clock::get()?.unix_timestamp,
+            withdrawn_in_period: 0,
+            pending_limit: None,
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            large_withdrawal_amount: 0,
+            approval_window_start: 0,
+            withdrawn_without_approval: 0,
+            proposal_count: 0,
+            pending_guardians: None,
+            beneficiary: None,
+            inactivity_timeout: 0,
+            pending_beneficiary: None,
+            last_heartbeat: Clock::get()?.unix_timestamp,
+            vault_id,
+            stream_count: 0,
+            active_streams: 0,
+            total_deposited: 0,
+            total_withdrawn: 0,
+            total_stake_rewards: 0,
+            created_at: Clock::get()?.unix_timestamp,
+            last_activity: Clock::get()?.unix_timestamp,
+            stake_accounts: Vec::new(),
+            stake_count: 0,
+        });
+
+        self.vault_index.user = self.user.key();
+        self.vault_index.bump = bumps.vault_index;
+        self.vault_index.add(vault_id)
+    }
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+use anchor_lang::Discriminator;
+
+use crate::{errors::VaultError, LegacyVaultState, VaultIndex, VaultState};
+
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: owner and discriminator are checked before the legacy layout is read in `migrate_vault`
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref()],
+        bump
+    )]
+    pub legacy_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref()],
+        bump
+    )]
+    pub legacy_vault: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"state", user.key().as_ref(), vault_id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + VaultState::INIT_SPACE,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"index", user.key().as_ref()],
+        bump,
+        space = 8 + VaultIndex::INIT_SPACE,
+    )]
+    pub vault_index: Account<'info, VaultIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateVault<'info> {
+    /// Moves a single-vault layout account over to `vault_id`. The old layout
+    /// carried no settings, so the new vault starts out like a fresh `initialize`
+    /// holding the legacy vault's lamports.
+    pub fn migrate_vault(&mut self, vault_id: u64, bumps: &MigrateVaultBumps) -> Result<()> {
+        self.read_legacy_state()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.vault_state.set_inner(VaultState {
+            vault_bump: bumps.vault,
+            state_bump: bumps.vault_state,
+            unlock_at: 0,
+            max_withdraw_per_period: None,
+            period_seconds: 0,
+            period_start: now,
+            withdrawn_in_period: 0,
+            pending_limit: None,
+            guardians: Vec::new(),
+            guardian_threshold: 0,
+            large_withdrawal_amount: 0,
+            approval_window_start: 0,
+            withdrawn_without_approval: 0,
+            proposal_count: 0,
+            pending_guardians: None,
+            beneficiary: None,
+            inactivity_timeout: 0,
+            pending_beneficiary: None,
+            last_heartbeat: now,
+            vault_id,
+            stream_count: 0,
+            active_streams: 0,
+            total_deposited: self.legacy_vault.lamports(),
+            total_withdrawn: 0,
+            total_stake_rewards: 0,
+            created_at: now,
+            last_activity: now,
+            stake_accounts: Vec::new(),
+            stake_count: 0,
+        });
+
+        self.vault_index.user = self.user.key();
+        self.vault_index.bump = bumps.vault_index;
+        self.vault_index.add(vault_id)?;
+
+        self.move_legacy_lamports(bumps.legacy_vault)?;
+        self.close_legacy_state()
+    }
+
+    /// The stored bumps are not used: the single-vault program wrote them the
+    /// wrong way round, and both are re-derived from the seeds above anyway.
+    fn read_legacy_state(&self) -> Result<LegacyVaultState> {
+        require_keys_eq!(*self.legacy_state.owner, crate::ID, VaultError::InvalidLegacyVault);
+
+        let data = self.legacy_state.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == VaultState::DISCRIMINATOR,
+            VaultError::InvalidLegacyVault
+        );
+
+        LegacyVaultState::deserialize(&mut &data[8..]).map_err(|_| error!(VaultError::InvalidLegacyVault))
+    }
+
+    fn move_legacy_lamports(&self, legacy_vault_bump: u8) -> Result<()> {
+        let cpi_program = self.system_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.legacy_vault.to_account_info(),
+            to: self.vault.to_account_info(),
+        };
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            &[legacy_vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, self.legacy_vault.lamports())
+    }
+
+    fn close_legacy_state(&self) -> Result<()> {
+        let legacy_state = self.legacy_state.to_account_info();
+        let user = self.user.to_account_info();
+
+        **user.try_borrow_mut_lamports()? += legacy_state.lamports();
+        **legacy_state.try_borrow_mut_lamports()? = 0;
+
+        legacy_state.assign(&System::id());
+        legacy_state.realloc(0, false)?;
+        Ok(())
+    }
+}
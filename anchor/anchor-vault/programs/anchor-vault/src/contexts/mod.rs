@@ -0,0 +1,68 @@
+pub mod initialize;
+pub use initialize::*;
+
+pub mod deposit;
+pub use deposit::*;
+
+pub mod withdraw;
+pub use withdraw::*;
+
+pub mod close;
+pub use close::*;
+
+pub mod extend_lock;
+pub use extend_lock::*;
+
+pub mod update_withdraw_limit;
+pub use update_withdraw_limit::*;
+
+pub mod update_guardians;
+pub use update_guardians::*;
+
+pub mod propose_withdrawal;
+pub use propose_withdrawal::*;
+
+pub mod approve_withdrawal;
+pub use approve_withdrawal::*;
+
+pub mod execute_withdrawal;
+pub use execute_withdrawal::*;
+
+pub mod cancel_withdrawal;
+pub use cancel_withdrawal::*;
+
+pub mod set_beneficiary;
+pub use set_beneficiary::*;
+
+pub mod heartbeat;
+pub use heartbeat::*;
+
+pub mod claim;
+pub use claim::*;
+
+pub mod claim_stream;
+pub use claim_stream::*;
+
+pub mod migrate_vault;
+pub use migrate_vault::*;
+
+pub mod create_stream;
+pub use create_stream::*;
+
+pub mod withdraw_streamed;
+pub use withdraw_streamed::*;
+
+pub mod cancel_stream;
+pub use cancel_stream::*;
+
+pub mod reconcile;
+pub use reconcile::*;
+
+pub mod stake_from_vault;
+pub use stake_from_vault::*;
+
+pub mod deactivate_stake;
+pub use deactivate_stake::*;
+
+pub mod withdraw_stake;
+pub use withdraw_stake::*;
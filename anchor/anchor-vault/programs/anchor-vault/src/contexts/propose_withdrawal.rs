@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, VaultState, WithdrawalProposal};
+
+#[derive(Accounts)]
+pub struct ProposeWithdrawal<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + WithdrawalProposal::INIT_SPACE,
+        seeds = [b"proposal", vault_state.key().as_ref(), vault_state.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, WithdrawalProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeWithdrawal<'info> {
+    pub fn propose_withdrawal(&mut self, amount: u64, bumps: &ProposeWithdrawalBumps) -> Result<()> {
+        self.proposal.set_inner(WithdrawalProposal {
+            vault_state: self.vault_state.key(),
+            id: self.vault_state.proposal_count,
+            amount,
+            approvals: Vec::new(),
+            bump: bumps.proposal,
+        });
+
+        self.vault_state.proposal_count = self.vault_state.proposal_count.checked_add(1).ok_or(VaultError::Overflow)?;
+        Ok(())
+    }
+}
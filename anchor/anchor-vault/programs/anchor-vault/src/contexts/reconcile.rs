@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, VaultState};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct Reconciliation {
+    pub balance: u64,
+    pub staked: u64,
+    pub principal: u64,
+    /// Lamports that reached the vault without going through `deposit` or a stake withdrawal.
+    pub external: u64,
+}
+
+#[derive(Accounts)]
+pub struct Reconcile<'info> {
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> Reconcile<'info> {
+    pub fn reconcile(&self) -> Result<Reconciliation> {
+        let balance = self.vault.lamports();
+        let staked = self.vault_state.staked_lamports()?;
+        let principal = self.vault_state.principal()?;
+
+        let held = balance.checked_add(staked).ok_or(VaultError::Overflow)?;
+
+        Ok(Reconciliation {
+            balance,
+            staked,
+            principal,
+            external: held.saturating_sub(principal),
+        })
+    }
+}
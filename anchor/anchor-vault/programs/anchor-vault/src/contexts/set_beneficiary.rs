@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_CHANGE_DELAY, MIN_INACTIVITY_TIMEOUT},
+    errors::VaultError,
+    PendingBeneficiary, VaultState,
+};
+
+#[derive(Accounts)]
+pub struct SetBeneficiary<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> SetBeneficiary<'info> {
+    pub fn set_beneficiary(&mut self, beneficiary: Option<Pubkey>, inactivity_timeout: i64) -> Result<()> {
+        // Clearing the beneficiary only takes a claim away, so it does not need to wait.
+        let Some(beneficiary) = beneficiary else {
+            self.vault_state.beneficiary = None;
+            self.vault_state.inactivity_timeout = inactivity_timeout;
+            self.vault_state.pending_beneficiary = None;
+            return self.vault_state.record_heartbeat();
+        };
+
+        require!(inactivity_timeout >= MIN_INACTIVITY_TIMEOUT, VaultError::InvalidInactivityTimeout);
+
+        let now = Clock::get()?.unix_timestamp;
+        self.vault_state.pending_beneficiary = Some(PendingBeneficiary {
+            beneficiary,
+            inactivity_timeout,
+            effective_at: now.checked_add(CONFIG_CHANGE_DELAY).ok_or(VaultError::Overflow)?,
+        });
+        self.vault_state.record_heartbeat()
+    }
+
+    pub fn apply_beneficiary(&mut self) -> Result<()> {
+        let pending = self.vault_state.pending_beneficiary.take().ok_or(VaultError::NoPendingBeneficiary)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending.effective_at, VaultError::BeneficiaryChangeNotReady);
+
+        self.vault_state.beneficiary = Some(pending.beneficiary);
+        self.vault_state.inactivity_timeout = pending.inactivity_timeout;
+        self.vault_state.record_heartbeat()
+    }
+}
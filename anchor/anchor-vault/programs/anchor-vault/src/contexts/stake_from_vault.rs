@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    program::{invoke, invoke_signed},
+    stake::{
+        self,
+        instruction as stake_instruction,
+        state::{Authorized, Lockup, StakeStateV2},
+    },
+    system_instruction,
+    sysvar::stake_history,
+    vote,
+};
+
+use crate::{constants::STAKE_CONFIG_ID, VaultState};
+
+#[derive(Accounts)]
+pub struct StakeFromVault<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    /// CHECK: created and initialized as a stake account in `stake_from_vault`
+    #[account(
+        mut,
+        seeds = [b"stake", vault_state.key().as_ref(), vault_state.stake_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+    /// CHECK: vote account the stake is delegated to, validated by the stake program
+    #[account(owner = vote::program::ID)]
+    pub validator_vote: UncheckedAccount<'info>,
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    /// CHECK: stake history sysvar
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+    /// CHECK: stake config account
+    #[account(address = STAKE_CONFIG_ID)]
+    pub stake_config: UncheckedAccount<'info>,
+    /// CHECK: native stake program
+    #[account(address = stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeFromVault<'info> {
+    /// Moves `amount` lamports into a new stake account, with the vault as both
+    /// staker and withdrawer, and delegates it to `validator_vote`.
+    pub fn stake_from_vault(&mut self, amount: u64, bumps: &StakeFromVaultBumps) -> Result<()> {
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let vault_seeds: &[&[u8]] = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+
+        let vault_state_key = self.vault_state.key();
+        let stake_count = self.vault_state.stake_count.to_le_bytes();
+        let stake_seeds: &[&[u8]] = &[
+            b"stake",
+            vault_state_key.as_ref(),
+            stake_count.as_ref(),
+            &[bumps.stake_account],
+        ];
+
+        invoke_signed(
+            &system_instruction::create_account(
+                self.vault.key,
+                self.stake_account.key,
+                amount,
+                StakeStateV2::size_of() as u64,
+                &stake::program::ID,
+            ),
+            &[
+                self.vault.to_account_info(),
+                self.stake_account.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+            &[vault_seeds, stake_seeds],
+        )?;
+
+        let authorized = Authorized {
+            staker: self.vault.key(),
+            withdrawer: self.vault.key(),
+        };
+        invoke(
+            &stake_instruction::initialize(self.stake_account.key, &authorized, &Lockup::default()),
+            &[
+                self.stake_account.to_account_info(),
+                self.rent.to_account_info(),
+                self.stake_program.to_account_info(),
+            ],
+        )?;
+
+        invoke_signed(
+            &stake_instruction::delegate_stake(self.stake_account.key, self.vault.key, self.validator_vote.key),
+            &[
+                self.stake_account.to_account_info(),
+                self.validator_vote.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.vault.to_account_info(),
+                self.stake_program.to_account_info(),
+            ],
+            &[vault_seeds],
+        )?;
+
+        self.vault_state.add_stake_account(self.stake_account.key(), amount)?;
+        self.vault_state.record_heartbeat()
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::CONFIG_CHANGE_DELAY, errors::VaultError, PendingGuardians, VaultState};
+
+#[derive(Accounts)]
+pub struct UpdateGuardians<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> UpdateGuardians<'info> {
+    pub fn set_guardians(&mut self, guardians: Vec<Pubkey>, guardian_threshold: u8, large_withdrawal_amount: u64) -> Result<()> {
+        VaultState::validate_guardians(&guardians, guardian_threshold)?;
+
+        // Adding the first guardians only tightens control, so it does not need to wait.
+        let delay = if self.vault_state.guardians.is_empty() { 0 } else { CONFIG_CHANGE_DELAY };
+
+        let now = Clock::get()?.unix_timestamp;
+        self.vault_state.pending_guardians = Some(PendingGuardians {
+            guardians,
+            guardian_threshold,
+            large_withdrawal_amount,
+            effective_at: now.checked_add(delay).ok_or(VaultError::Overflow)?,
+        });
+        Ok(())
+    }
+
+    pub fn apply_guardians(&mut self) -> Result<()> {
+        let pending = self.vault_state.pending_guardians.take().ok_or(VaultError::NoPendingGuardians)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending.effective_at, VaultError::GuardianChangeNotReady);
+
+        self.vault_state.guardians = pending.guardians;
+        self.vault_state.guardian_threshold = pending.guardian_threshold;
+        self.vault_state.large_withdrawal_amount = pending.large_withdrawal_amount;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::CONFIG_CHANGE_DELAY, errors::VaultError, PendingLimit, VaultState};
+
+#[derive(Accounts)]
+pub struct UpdateWithdrawLimit<'info> {
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+impl<'info> UpdateWithdrawLimit<'info> {
+    pub fn propose_withdraw_limit(&mut self, max_withdraw_per_period: Option<u64>, period_seconds: i64) -> Result<()> {
+        VaultState::validate_limit(max_withdraw_per_period, period_seconds)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.vault_state.pending_limit = Some(PendingLimit {
+            max_withdraw_per_period,
+            period_seconds,
+            effective_at: now.checked_add(CONFIG_CHANGE_DELAY).ok_or(VaultError::Overflow)?,
+        });
+        Ok(())
+    }
+
+    pub fn apply_withdraw_limit(&mut self) -> Result<()> {
+        let pending = self.vault_state.pending_limit.ok_or(VaultError::NoPendingLimit)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending.effective_at, VaultError::LimitChangeNotReady);
+
+        self.vault_state.max_withdraw_per_period = pending.max_withdraw_per_period;
+        self.vault_state.period_seconds = pending.period_seconds;
+        self.vault_state.period_start = now;
+        self.vault_state.withdrawn_in_period = 0;
+        self.vault_state.pending_limit = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{Transfer, transfer};
+
+use crate::{events::Withdrawn, VaultState};
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
+        self.vault_state.record_unapproved(amount)?;
+        self.vault_state.check_unlocked()?;
+        self.vault_state.record_withdrawal(amount)?;
+        self.vault_state.record_heartbeat()?;
+
+        let cpi_program: AccountInfo<'info> = self.system_program.to_account_info();
+        let cpi_accounts: Transfer<'info> = Transfer {
+            from: self.vault.to_account_info(),
+            to: self.user.to_account_info()
+        };
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx: CpiContext<'_, '_, '_, 'info, Transfer<'info>> =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        transfer(cpi_ctx, amount)?;
+
+        emit!(Withdrawn {
+            vault_state: self.vault_state.key(),
+            to: self.user.key(),
+            amount,
+            total_withdrawn: self.vault_state.total_withdrawn,
+            timestamp: self.vault_state.last_activity,
+        });
+        Ok(())
+    }
+}
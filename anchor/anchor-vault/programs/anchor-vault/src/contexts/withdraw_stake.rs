@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    program::invoke_signed,
+    stake::{self, instruction as stake_instruction},
+    sysvar::stake_history,
+};
+
+use crate::VaultState;
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    /// Anyone can sweep the stake of an inactive owner, since it can only land in the vault.
+    pub authority: Signer<'info>,
+
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.vault_bump
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    /// CHECK: must be one of the stake accounts tracked in `vault_state`
+    #[account(mut)]
+    pub stake_account: UncheckedAccount<'info>,
+    pub clock: Sysvar<'info, Clock>,
+    /// CHECK: stake history sysvar
+    #[account(address = stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+    /// CHECK: native stake program
+    #[account(address = stake::program::ID)]
+    pub stake_program: UncheckedAccount<'info>,
+}
+
+impl<'info> WithdrawStake<'info> {
+    /// Pulls every lamport, rewards included, from a deactivated stake account back into the vault.
+    pub fn withdraw_stake(&mut self) -> Result<()> {
+        self.vault_state.check_stake_account(self.stake_account.key)?;
+        let is_owner = self.authority.key() == self.user.key();
+        if !is_owner {
+            self.vault_state.check_owner_inactive()?;
+        }
+
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let seeds = &[
+            b"vault",
+            self.user.to_account_info().key.as_ref(),
+            vault_id.as_ref(),
+            &[self.vault_state.vault_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let amount = self.stake_account.lamports();
+        invoke_signed(
+            &stake_instruction::withdraw(
+                self.stake_account.key,
+                self.vault.key,
+                self.vault.key,
+                amount,
+                None,
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.vault.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_program.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+
+        self.vault_state.remove_stake_account(self.stake_account.key, amount)?;
+        if is_owner {
+            self.vault_state.record_heartbeat()?;
+        }
+        Ok(())
+    }
+}
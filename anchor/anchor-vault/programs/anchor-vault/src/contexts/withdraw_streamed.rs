@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, Stream, VaultState};
+
+#[derive(Accounts)]
+pub struct WithdrawStreamed<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// Vault owner, who gets the stream rent back once it is fully paid out.
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"state", user.key().as_ref(), vault_state.vault_id.to_le_bytes().as_ref()],
+        bump = vault_state.state_bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        mut,
+        has_one = vault_state,
+        has_one = recipient,
+        seeds = [b"stream", vault_state.key().as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+impl<'info> WithdrawStreamed<'info> {
+    pub fn withdraw_streamed(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let vested = self.stream.vested_amount(now)?;
+        let amount = vested.checked_sub(self.stream.withdrawn).ok_or(VaultError::Overflow)?;
+        require!(amount > 0, VaultError::NothingVested);
+
+        self.stream.withdrawn = vested;
+        self.stream.sub_lamports(amount)?;
+        self.recipient.add_lamports(amount)?;
+
+        if self.stream.withdrawn == self.stream.total {
+            self.vault_state.active_streams = self.vault_state.active_streams.checked_sub(1).ok_or(VaultError::Overflow)?;
+            self.stream.close(self.user.to_account_info())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::error_code;
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Vault is still locked.")]
+    VaultLocked,
+    #[msg("Unlock time can only be extended.")]
+    LockCannotBeShortened,
+    #[msg("Withdrawal exceeds the limit for this period.")]
+    WithdrawLimitExceeded,
+    #[msg("A withdraw limit needs a positive period.")]
+    InvalidWithdrawLimit,
+    #[msg("No withdraw limit change is pending.")]
+    NoPendingLimit,
+    #[msg("Withdraw limit change is still time-locked.")]
+    LimitChangeNotReady,
+    #[msg("Overflow detected.")]
+    Overflow,
+    #[msg("Too many guardians.")]
+    TooManyGuardians,
+    #[msg("Guardian listed more than once.")]
+    DuplicateGuardian,
+    #[msg("Guardian threshold must be between 1 and the number of guardians.")]
+    InvalidGuardianThreshold,
+    #[msg("No guardian change is pending.")]
+    NoPendingGuardians,
+    #[msg("Guardian change is still time-locked.")]
+    GuardianChangeNotReady,
+    #[msg("Withdrawal needs guardian approval.")]
+    ApprovalRequired,
+    #[msg("Signer is not a guardian of this vault.")]
+    NotGuardian,
+    #[msg("Guardian already approved this withdrawal.")]
+    AlreadyApproved,
+    #[msg("Not enough guardian approvals.")]
+    InsufficientApprovals,
+    #[msg("Inactivity timeout is too short.")]
+    InvalidInactivityTimeout,
+    #[msg("No beneficiary change is pending.")]
+    NoPendingBeneficiary,
+    #[msg("Beneficiary change is still time-locked.")]
+    BeneficiaryChangeNotReady,
+    #[msg("Signer is not the beneficiary of this vault.")]
+    NotBeneficiary,
+    #[msg("Vault owner is still active.")]
+    OwnerStillActive,
+    #[msg("Vault index is full.")]
+    TooManyVaults,
+    #[msg("Account is not a legacy vault.")]
+    InvalidLegacyVault,
+    #[msg("Stream needs a positive total and start <= cliff <= end, with start < end.")]
+    InvalidStreamSchedule,
+    #[msg("Nothing has vested yet.")]
+    NothingVested,
+    #[msg("Vault still has open streams.")]
+    StreamsOutstanding,
+    #[msg("Vault has too many stake accounts.")]
+    TooManyStakeAccounts,
+    #[msg("Stake account does not belong to this vault.")]
+    UnknownStakeAccount,
+    #[msg("Vault still has stake accounts.")]
+    StakeAccountsOutstanding,
+}
@@ -1,16 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{Transfer, transfer};
-use anchor_lang::solana_program::program::invoke_signed;
-use anchor_lang::solana_program::system_instruction;
 
 declare_id!("GhJ9VpsWDDu3Zum2XaTkScHh83wqXB94iUrYUnEFQvin");
 
+pub mod constants;
+pub mod errors;
+pub mod events;
+pub mod state;
+pub use state::*;
+pub mod contexts;
+pub use contexts::*;
+
 #[program]
 pub mod anchor_vault {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        ctx.accounts.initialize(&ctx.bumps)?;
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        vault_id: u64,
+        unlock_at: i64,
+        max_withdraw_per_period: Option<u64>,
+        period_seconds: i64,
+    ) -> Result<()> {
+        ctx.accounts.initialize(vault_id, unlock_at, max_withdraw_per_period, period_seconds, &ctx.bumps)?;
 
         Ok(())
     }
@@ -32,168 +43,157 @@ pub mod anchor_vault {
 
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize <'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        init,
-        payer = user,
-        seeds = [b"state", user.key().as_ref()], 
-        bump,
-        space = VaultState::INIT_SPACE,
-    )]
-
-    pub vault_state: Account<'info, VaultState>,
-    #[account(
-        mut,
-        seeds = [b"vault", user.key().as_ref()],
-        bump
-    )]
-
-    pub vault: SystemAccount<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    pub fn extend_lock(ctx: Context<ExtendLock>, unlock_at: i64) -> Result<()> {
+        ctx.accounts.extend_lock(unlock_at)?;
 
-impl<'info> Initialize<'info> {
-    pub fn initialize(&mut self, bumps: &InitializeBumps) -> Result<()> {
-        self.vault_state.vault_bump = bumps.vault_state;
-        self.vault_state.state_bump = bumps.vault;
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Deposit<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"vault", user.key().as_ref()],
-        bump = vault_state.state_bump
-    )]
-
-    pub vault: SystemAccount<'info>,
-    #[account(
-        seeds = [b"state", user.key().as_ref()],
-        bump = vault_state.vault_bump
-    )]
-    pub vault_state: Account<'info, VaultState>,
-    pub system_program: Program<'info, System>,
+    pub fn propose_withdraw_limit(
+        ctx: Context<UpdateWithdrawLimit>,
+        max_withdraw_per_period: Option<u64>,
+        period_seconds: i64,
+    ) -> Result<()> {
+        ctx.accounts.propose_withdraw_limit(max_withdraw_per_period, period_seconds)?;
 
-}
+        Ok(())
+    }
 
-impl<'info> Deposit<'info> {
-    pub fn deposit(&mut self, amount: u64) -> Result<()> {
-        let cpi_program: AccountInfo<'info> = self.system_program.to_account_info();
-        
-        let cpi_accounts: Transfer<'info> = Transfer {
-            from: self.user.to_account_info(),
-            to: self.vault.to_account_info()
-        };
+    pub fn apply_withdraw_limit(ctx: Context<UpdateWithdrawLimit>) -> Result<()> {
+        ctx.accounts.apply_withdraw_limit()?;
 
-        let cpi_ctx: CpiContext<'_, '_, '_, 'info, Transfer<'info>> = CpiContext::new(cpi_program, cpi_accounts);
-        transfer(cpi_ctx, amount)?;
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Withdraw<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"vault", user.key().as_ref()],
-        bump = vault_state.vault_bump
-    )]
-    pub vault: SystemAccount<'info>,
-    #[account(
-        seeds = [b"state", user.key().as_ref()],
-        bump = vault_state.state_bump
-    )]
-    pub vault_state: Account<'info, VaultState>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn set_guardians(
+        ctx: Context<UpdateGuardians>,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
+        large_withdrawal_amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.set_guardians(guardians, guardian_threshold, large_withdrawal_amount)?;
 
-impl<'info> Withdraw<'info> {
-    pub fn withdraw(&mut self, amount: u64) -> Result<()> {
-        let cpi_program: AccountInfo<'info> = self.system_program.to_account_info();
-        let cpi_accounts: Transfer<'info> = Transfer {
-            from: self.vault.to_account_info(),
-            to: self.user.to_account_info()
-        };
-        let seeds = &[
-            b"vault",
-            self.vault_state.to_account_info().key.as_ref(),
-            &[self.vault_state.vault_bump],
-        ];
-        let signer_seeds = &[&seeds[..]];
-
-        let cpi_ctx: CpiContext<'_, '_, '_, 'info, Transfer<'info>> =
-            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-        transfer(cpi_ctx, amount)?;
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Close<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"vault", user.key().as_ref()],
-        bump = vault_state.state_bump
-    )]
-    pub vault: SystemAccount<'info>,
-    #[account(
-        mut,
-        seeds = [b"state", user.key().as_ref()],
-        bump = vault_state.vault_bump,
-        close = user
-    )]
-    pub vault_state: Account<'info, VaultState>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn apply_guardians(ctx: Context<UpdateGuardians>) -> Result<()> {
+        ctx.accounts.apply_guardians()?;
 
-impl<'info> Close<'info> {
-    pub fn close(&mut self) -> Result<()> {
-        let cpi_program = self.system_program.to_account_info();
+        Ok(())
+    }
 
-        let cpi_accounts = Transfer {
-            from: self.vault.to_account_info(),
-            to: self.user.to_account_info(),
-        };
+    pub fn propose_withdrawal(ctx: Context<ProposeWithdrawal>, amount: u64) -> Result<()> {
+        ctx.accounts.propose_withdrawal(amount, &ctx.bumps)?;
 
-        let seeds = &[
-            b"vault",
-            self.vault_state.to_account_info().key.as_ref(),
-            &[self.vault_state.vault_bump],
-        ];
+        Ok(())
+    }
 
-        let signer_seeds = &[&seeds[..]];
+    pub fn approve_withdrawal(ctx: Context<ApproveWithdrawal>) -> Result<()> {
+        ctx.accounts.approve_withdrawal()?;
 
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        Ok(())
+    }
 
-        transfer(cpi_ctx, self.vault.lamports())?;
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>) -> Result<()> {
+        ctx.accounts.execute_withdrawal()?;
 
         Ok(())
     }
-}
 
+    /// Drops a pending withdrawal proposal and returns its rent to the owner.
+    pub fn cancel_withdrawal(_ctx: Context<CancelWithdrawal>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_beneficiary(
+        ctx: Context<SetBeneficiary>,
+        beneficiary: Option<Pubkey>,
+        inactivity_timeout: i64,
+    ) -> Result<()> {
+        ctx.accounts.set_beneficiary(beneficiary, inactivity_timeout)?;
 
+        Ok(())
+    }
+
+    pub fn apply_beneficiary(ctx: Context<SetBeneficiary>) -> Result<()> {
+        ctx.accounts.apply_beneficiary()?;
+
+        Ok(())
+    }
+
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        ctx.accounts.heartbeat()?;
+
+        Ok(())
+    }
+
+    /// Lets the beneficiary take the whole vault once the owner has been inactive for the timeout.
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        ctx.accounts.claim()?;
+
+        Ok(())
+    }
 
-#[account]
-#[derive(InitSpace)]
-pub struct VaultState {
-    pub vault_bump: u8,
-    pub state_bump: u8,
-}
\ No newline at end of file
+    /// Lets the beneficiary of an inactive owner cancel a stream that would otherwise block `claim`.
+    pub fn claim_stream(ctx: Context<ClaimStream>) -> Result<()> {
+        ctx.accounts.claim_stream()?;
+
+        Ok(())
+    }
+
+    /// Moves a vault from the old one-per-user `[b"state", user]` layout to `vault_id`.
+    pub fn migrate_vault(ctx: Context<MigrateVault>, vault_id: u64) -> Result<()> {
+        ctx.accounts.migrate_vault(vault_id, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
+        recipient: Pubkey,
+        total: u64,
+        start: i64,
+        end: i64,
+        cliff: i64,
+    ) -> Result<()> {
+        ctx.accounts.create_stream(recipient, total, start, end, cliff, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_streamed(ctx: Context<WithdrawStreamed>) -> Result<()> {
+        ctx.accounts.withdraw_streamed()?;
+
+        Ok(())
+    }
+
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        ctx.accounts.cancel_stream()?;
+
+        Ok(())
+    }
+
+    /// Read-only: reports the vault balance against recorded deposits and withdrawals.
+    pub fn reconcile(ctx: Context<Reconcile>) -> Result<Reconciliation> {
+        ctx.accounts.reconcile()
+    }
+
+    pub fn stake_from_vault(ctx: Context<StakeFromVault>, amount: u64) -> Result<()> {
+        ctx.accounts.stake_from_vault(amount, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn deactivate_stake(ctx: Context<DeactivateStake>) -> Result<()> {
+        ctx.accounts.deactivate_stake()?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        ctx.accounts.withdraw_stake()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+/// Layout of `VaultState` as first deployed, when each user had a single vault
+/// at `[b"state", user]` and the account held nothing but its two bumps. Only
+/// read by `migrate_vault`.
+#[derive(AnchorDeserialize)]
+pub struct LegacyVaultState {
+    pub vault_bump: u8,
+    pub state_bump: u8,
+}
@@ -0,0 +1,14 @@
+pub mod vault_state;
+pub use vault_state::*;
+
+pub mod withdrawal_proposal;
+pub use withdrawal_proposal::*;
+
+pub mod vault_index;
+pub use vault_index::*;
+
+pub mod legacy_vault_state;
+pub use legacy_vault_state::*;
+
+pub mod stream;
+pub use stream::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VaultError;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Stream {
+    pub vault_state: Pubkey,
+    pub recipient: Pubkey,
+    pub id: u64,
+    pub total: u64,
+    pub withdrawn: u64,
+    pub start: i64,
+    pub end: i64,
+    pub cliff: i64,
+    pub bump: u8,
+}
+
+impl Stream {
+    pub fn validate_schedule(total: u64, start: i64, end: i64, cliff: i64) -> Result<()> {
+        require!(total > 0, VaultError::InvalidStreamSchedule);
+        require!(start < end, VaultError::InvalidStreamSchedule);
+        require!(start <= cliff && cliff <= end, VaultError::InvalidStreamSchedule);
+        Ok(())
+    }
+
+    /// Amount vested at `now`: nothing before the cliff, then linear from `start` to `end`.
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if now < self.cliff {
+            return Ok(0);
+        }
+        if now >= self.end {
+            return Ok(self.total);
+        }
+
+        let elapsed = now.checked_sub(self.start).ok_or(VaultError::Overflow)? as u128;
+        let duration = self.end.checked_sub(self.start).ok_or(VaultError::Overflow)? as u128;
+
+        let vested = (self.total as u128)
+            .checked_mul(elapsed)
+            .ok_or(VaultError::Overflow)?
+            .checked_div(duration)
+            .ok_or(VaultError::Overflow)?;
+
+        Ok(vested as u64)
+    }
+
+    /// Splits what is left in the stream at `now` into the part owed to the
+    /// recipient (vested but not withdrawn) and the unvested rest.
+    pub fn split_remaining(&self, now: i64) -> Result<(u64, u64)> {
+        let vested = self.vested_amount(now)?;
+
+        let owed = vested.checked_sub(self.withdrawn).ok_or(VaultError::Overflow)?;
+        let unvested = self.total.checked_sub(vested).ok_or(VaultError::Overflow)?;
+        Ok((owed, unvested))
+    }
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::MAX_VAULTS_PER_USER, errors::VaultError};
+
+#[account]
+#[derive(InitSpace)]
+pub struct VaultIndex {
+    pub user: Pubkey,
+    #[max_len(MAX_VAULTS_PER_USER)]
+    pub vault_ids: Vec<u64>,
+    pub bump: u8,
+}
+
+impl VaultIndex {
+    pub fn add(&mut self, vault_id: u64) -> Result<()> {
+        require!(self.vault_ids.len() < MAX_VAULTS_PER_USER, VaultError::TooManyVaults);
+
+        self.vault_ids.push(vault_id);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, vault_id: u64) {
+        self.vault_ids.retain(|id| *id != vault_id);
+    }
+}
@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{APPROVAL_WINDOW, MAX_GUARDIANS, MAX_STAKE_ACCOUNTS},
+    errors::VaultError,
+    WithdrawalProposal,
+};
+
+#[account]
+#[derive(InitSpace)]
+pub struct VaultState {
+    pub vault_bump: u8,
+    pub state_bump: u8,
+    pub unlock_at: i64,
+    pub max_withdraw_per_period: Option<u64>,
+    pub period_seconds: i64,
+    pub period_start: i64,
+    pub withdrawn_in_period: u64,
+    pub pending_limit: Option<PendingLimit>,
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u8,
+    pub large_withdrawal_amount: u64,
+    pub approval_window_start: i64,
+    pub withdrawn_without_approval: u64,
+    pub proposal_count: u64,
+    pub pending_guardians: Option<PendingGuardians>,
+    pub beneficiary: Option<Pubkey>,
+    pub inactivity_timeout: i64,
+    pub pending_beneficiary: Option<PendingBeneficiary>,
+    pub last_heartbeat: i64,
+    pub vault_id: u64,
+    pub stream_count: u64,
+    pub active_streams: u32,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub total_stake_rewards: u64,
+    pub created_at: i64,
+    pub last_activity: i64,
+    #[max_len(MAX_STAKE_ACCOUNTS)]
+    pub stake_accounts: Vec<StakeRecord>,
+    pub stake_count: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct StakeRecord {
+    pub stake_account: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct PendingLimit {
+    pub max_withdraw_per_period: Option<u64>,
+    pub period_seconds: i64,
+    pub effective_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PendingGuardians {
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub guardian_threshold: u8,
+    pub large_withdrawal_amount: u64,
+    pub effective_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct PendingBeneficiary {
+    pub beneficiary: Pubkey,
+    pub inactivity_timeout: i64,
+    pub effective_at: i64,
+}
+
+impl VaultState {
+    pub fn check_unlocked(&self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.unlock_at, VaultError::VaultLocked);
+        Ok(())
+    }
+
+    /// Resets the dead-man switch; called on every owner deposit, withdraw and heartbeat.
+    pub fn record_heartbeat(&mut self) -> Result<()> {
+        self.last_heartbeat = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// The beneficiary can act on the vault once the owner has gone `inactivity_timeout`
+    /// without a heartbeat.
+    pub fn check_claimable(&self, beneficiary: &Pubkey) -> Result<()> {
+        require!(self.beneficiary == Some(*beneficiary), VaultError::NotBeneficiary);
+        self.check_owner_inactive()
+    }
+
+    /// Only a vault with a beneficiary can be treated as abandoned.
+    pub fn check_owner_inactive(&self) -> Result<()> {
+        require!(self.beneficiary.is_some(), VaultError::OwnerStillActive);
+
+        let now = Clock::get()?.unix_timestamp;
+        let claimable_at = self
+            .last_heartbeat
+            .checked_add(self.inactivity_timeout)
+            .ok_or(VaultError::Overflow)?;
+        require!(now >= claimable_at, VaultError::OwnerStillActive);
+        Ok(())
+    }
+
+    /// Funding a stream counted as a withdrawal; undoes that for the unvested part
+    /// a cancelled stream returns to the vault.
+    pub fn record_stream_cancelled(&mut self, unvested: u64) -> Result<()> {
+        self.total_withdrawn = self.total_withdrawn.checked_sub(unvested).ok_or(VaultError::Overflow)?;
+        self.active_streams = self.active_streams.checked_sub(1).ok_or(VaultError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn validate_limit(max_withdraw_per_period: Option<u64>, period_seconds: i64) -> Result<()> {
+        if max_withdraw_per_period.is_some() {
+            require!(period_seconds > 0, VaultError::InvalidWithdrawLimit);
+        }
+        Ok(())
+    }
+
+    pub fn validate_guardians(guardians: &[Pubkey], guardian_threshold: u8) -> Result<()> {
+        require!(guardians.len() <= MAX_GUARDIANS, VaultError::TooManyGuardians);
+
+        for (i, guardian) in guardians.iter().enumerate() {
+            require!(!guardians[..i].contains(guardian), VaultError::DuplicateGuardian);
+        }
+
+        if guardians.is_empty() {
+            require_eq!(guardian_threshold, 0, VaultError::InvalidGuardianThreshold);
+        } else {
+            require!(
+                guardian_threshold > 0 && guardian_threshold as usize <= guardians.len(),
+                VaultError::InvalidGuardianThreshold
+            );
+        }
+        Ok(())
+    }
+
+    /// Counts `amount` against what can leave the vault without guardians within
+    /// the current `APPROVAL_WINDOW`. Anything above `large_withdrawal_amount` in
+    /// total must go through a guardian-approved proposal instead.
+    pub fn record_unapproved(&mut self, amount: u64) -> Result<()> {
+        if self.guardians.is_empty() {
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        if now.saturating_sub(self.approval_window_start) >= APPROVAL_WINDOW {
+            self.approval_window_start = now;
+            self.withdrawn_without_approval = 0;
+        }
+
+        let withdrawn = self.withdrawn_without_approval.checked_add(amount).ok_or(VaultError::Overflow)?;
+        require!(withdrawn <= self.large_withdrawal_amount, VaultError::ApprovalRequired);
+
+        self.withdrawn_without_approval = withdrawn;
+        Ok(())
+    }
+
+    /// Only approvals from guardians that are still in the current set count.
+    pub fn is_approved(&self, proposal: &WithdrawalProposal) -> bool {
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|approver| self.guardians.contains(approver))
+            .count();
+
+        approvals >= self.guardian_threshold as usize
+    }
+
+    pub fn record_deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_deposited = self.total_deposited.checked_add(amount).ok_or(VaultError::Overflow)?;
+        self.last_activity = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// Counts `amount` towards `total_withdrawn` and against the current withdraw
+    /// window, starting a new window once `period_seconds` have passed since the
+    /// last one began.
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        if let Some(max) = self.max_withdraw_per_period {
+            if now.saturating_sub(self.period_start) >= self.period_seconds {
+                self.period_start = now;
+                self.withdrawn_in_period = 0;
+            }
+
+            let withdrawn = self.withdrawn_in_period.checked_add(amount).ok_or(VaultError::Overflow)?;
+            require!(withdrawn <= max, VaultError::WithdrawLimitExceeded);
+
+            self.withdrawn_in_period = withdrawn;
+        }
+
+        self.total_withdrawn = self.total_withdrawn.checked_add(amount).ok_or(VaultError::Overflow)?;
+        self.last_activity = now;
+        Ok(())
+    }
+
+    pub fn add_stake_account(&mut self, stake_account: Pubkey, amount: u64) -> Result<()> {
+        require!(self.stake_accounts.len() < MAX_STAKE_ACCOUNTS, VaultError::TooManyStakeAccounts);
+
+        self.stake_accounts.push(StakeRecord { stake_account, amount });
+        self.stake_count = self.stake_count.checked_add(1).ok_or(VaultError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn check_stake_account(&self, stake_account: &Pubkey) -> Result<()> {
+        require!(
+            self.stake_accounts.iter().any(|record| record.stake_account == *stake_account),
+            VaultError::UnknownStakeAccount
+        );
+        Ok(())
+    }
+
+    /// Drops a stake account that has been withdrawn into the vault, counting
+    /// anything it returned above the staked amount as rewards.
+    pub fn remove_stake_account(&mut self, stake_account: &Pubkey, withdrawn: u64) -> Result<()> {
+        if let Some(record) = self.stake_accounts.iter().find(|record| record.stake_account == *stake_account) {
+            let rewards = withdrawn.saturating_sub(record.amount);
+            self.total_stake_rewards = self.total_stake_rewards.checked_add(rewards).ok_or(VaultError::Overflow)?;
+        }
+
+        self.stake_accounts.retain(|record| record.stake_account != *stake_account);
+        Ok(())
+    }
+
+    /// Lamports moved from the vault into stake accounts that have not been withdrawn yet.
+    pub fn staked_lamports(&self) -> Result<u64> {
+        self.stake_accounts
+            .iter()
+            .try_fold(0u64, |total, record| total.checked_add(record.amount))
+            .ok_or(error!(VaultError::Overflow))
+    }
+
+    /// Lamports the vault should hold if nothing reached it outside this program.
+    /// Withdrawing lamports that arrived by plain transfer can take withdrawals
+    /// past what came in, so this bottoms out at zero rather than failing.
+    pub fn principal(&self) -> Result<u64> {
+        let received = self
+            .total_deposited
+            .checked_add(self.total_stake_rewards)
+            .ok_or(VaultError::Overflow)?;
+
+        Ok(received.saturating_sub(self.total_withdrawn))
+    }
+}
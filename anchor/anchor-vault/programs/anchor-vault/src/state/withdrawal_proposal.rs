@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::MAX_GUARDIANS;
+
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalProposal {
+    pub vault_state: Pubkey,
+    pub id: u64,
+    pub amount: u64,
+    #[max_len(MAX_GUARDIANS)]
+    pub approvals: Vec<Pubkey>,
+    pub bump: u8,
+}
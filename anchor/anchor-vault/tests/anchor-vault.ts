@@ -1,16 +1,1224 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  VoteInit,
+  VoteProgram,
+} from "@solana/web3.js";
+import { assert } from "chai";
 import { AnchorVault } from "../target/types/anchor_vault";
+import IDL from "../target/idl/anchor_vault.json";
+
+const { BN } = anchor;
+
+// Advance one slot so a repeated transaction gets a fresh blockhash instead
+// of being rejected as already processed.
+const nextSlot = async (context: ProgramTestContext) => {
+  const clock = await context.banksClient.getClock();
+  context.warpToSlot(clock.slot + BigInt(1));
+};
+
+// Move the bank clock to `unixTimestamp`, keeping everything else as is.
+const warpTo = async (context: ProgramTestContext, unixTimestamp: number) => {
+  await nextSlot(context);
+  const clock = await context.banksClient.getClock();
+  context.setClock(
+    new Clock(
+      clock.slot,
+      clock.epochStartTimestamp,
+      clock.epoch,
+      clock.leaderScheduleEpoch,
+      BigInt(unixTimestamp)
+    )
+  );
+};
+
+const now = async (context: ProgramTestContext) =>
+  Number((await context.banksClient.getClock()).unixTimestamp);
+
+const expectError = async (promise: Promise<unknown>, code: string) => {
+  let error: unknown;
+  try {
+    await promise;
+  } catch (err) {
+    error = err;
+  }
+  assert.isDefined(error, `expected ${code}`);
+  assert.include(String(error), code);
+};
+
+const idSeed = (id: number) => new BN(id).toArrayLike(Buffer, "le", 8);
+
+const findVault = (programId: PublicKey, user: PublicKey, vaultId: number) => {
+  const [vaultState] = PublicKey.findProgramAddressSync(
+    [Buffer.from("state"), user.toBuffer(), idSeed(vaultId)],
+    programId
+  );
+  const [vault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), user.toBuffer(), idSeed(vaultId)],
+    programId
+  );
+  return { vaultState, vault };
+};
+
+const findProposal = (
+  programId: PublicKey,
+  vaultState: PublicKey,
+  id: number
+) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("proposal"), vaultState.toBuffer(), idSeed(id)],
+    programId
+  )[0];
 
 describe("anchor-vault", () => {
-  // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+
+  const LOCK_SECONDS = 60 * 60 * 24;
+  let unlockAt: number;
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+  });
+
+  it("Is initialized with an unlock time", async () => {
+    unlockAt = (await now(context)) + LOCK_SECONDS;
+    await program.methods
+      .initialize(new BN(0), new BN(unlockAt), null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.unlockAt.toNumber(), unlockAt);
+  });
+
+  it("Accepts deposits while locked", async () => {
+    await program.methods
+      .deposit(new BN(1_000_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 1_000_000_000);
+  });
+
+  it("Rejects withdraw before the unlock time", async () => {
+    await expectError(
+      program.methods.withdraw(new BN(1)).accountsPartial(accounts).rpc(),
+      "VaultLocked"
+    );
+  });
+
+  it("Rejects close before the unlock time", async () => {
+    await expectError(
+      program.methods
+        .close()
+        .accountsPartial(accounts)
+        .rpc(),
+      "VaultLocked"
+    );
+  });
+
+  it("Rejects shortening the lock", async () => {
+    await expectError(
+      program.methods
+        .extendLock(new BN(unlockAt - 1))
+        .accountsPartial(accounts)
+        .rpc(),
+      "LockCannotBeShortened"
+    );
+  });
+
+  it("Extends the lock", async () => {
+    unlockAt += LOCK_SECONDS;
+    await program.methods
+      .extendLock(new BN(unlockAt))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.unlockAt.toNumber(), unlockAt);
+  });
+
+  it("Stays locked until the extended unlock time", async () => {
+    await warpTo(context, unlockAt - 1);
+    await expectError(
+      program.methods.withdraw(new BN(1)).accountsPartial(accounts).rpc(),
+      "VaultLocked"
+    );
+  });
+
+  it("Withdraws once unlocked", async () => {
+    await warpTo(context, unlockAt);
+    await program.methods
+      .withdraw(new BN(400_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 600_000_000);
+  });
+
+  it("Closes once unlocked", async () => {
+    await program.methods.close().accountsPartial(accounts).rpc();
+
+    assert.isNull(await context.banksClient.getAccount(vaultState));
+    assert.equal(Number(await context.banksClient.getBalance(vault)), 0);
+  });
+});
+
+describe("anchor-vault withdraw limits", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+
+  const LIMIT = 100_000_000;
+  const PERIOD = 60 * 60;
+  const LIMIT_CHANGE_DELAY = 60 * 60 * 24 * 2;
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+
+    await program.methods
+      .initialize(new BN(0), new BN(0), new BN(LIMIT), new BN(PERIOD))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .deposit(new BN(1_000_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+  });
+
+  it("Withdraws up to the limit within a period", async () => {
+    await program.methods
+      .withdraw(new BN(60_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .withdraw(new BN(40_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.withdrawnInPeriod.toNumber(), LIMIT);
+  });
+
+  it("Rejects withdrawals over the limit", async () => {
+    await expectError(
+      program.methods.withdraw(new BN(1)).accountsPartial(accounts).rpc(),
+      "WithdrawLimitExceeded"
+    );
+  });
+
+  it("Resets the limit in the next period", async () => {
+    const state = await program.account.vaultState.fetch(vaultState);
+    await warpTo(context, state.periodStart.toNumber() + PERIOD);
+
+    await program.methods
+      .withdraw(new BN(LIMIT))
+      .accountsPartial(accounts)
+      .rpc();
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 800_000_000);
+  });
+
+  it("Rejects close while the balance is over the limit", async () => {
+    const state = await program.account.vaultState.fetch(vaultState);
+    await warpTo(context, state.periodStart.toNumber() + PERIOD);
+
+    await expectError(
+      program.methods
+        .close()
+        .accountsPartial(accounts)
+        .rpc(),
+      "WithdrawLimitExceeded"
+    );
+  });
+
+  it("Does not apply a limit change before the delay", async () => {
+    await program.methods
+      .proposeWithdrawLimit(null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+
+    await expectError(
+      program.methods.applyWithdrawLimit().accountsPartial(accounts).rpc(),
+      "LimitChangeNotReady"
+    );
+    await expectError(
+      program.methods
+        .withdraw(new BN(LIMIT + 1))
+        .accountsPartial(accounts)
+        .rpc(),
+      "WithdrawLimitExceeded"
+    );
+  });
+
+  it("Applies a limit change after the delay", async () => {
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(
+      state.pendingLimit.effectiveAt.toNumber(),
+      (await now(context)) + LIMIT_CHANGE_DELAY
+    );
+
+    await warpTo(context, state.pendingLimit.effectiveAt.toNumber());
+    await program.methods.applyWithdrawLimit().accountsPartial(accounts).rpc();
+
+    const updated = await program.account.vaultState.fetch(vaultState);
+    assert.isNull(updated.maxWithdrawPerPeriod);
+    assert.isNull(updated.pendingLimit);
+
+    await program.methods.close().accountsPartial(accounts).rpc();
+    assert.isNull(await context.banksClient.getAccount(vaultState));
+  });
+});
+
+describe("anchor-vault guardians", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+  let proposal: PublicKey;
+
+  const guardians = [
+    Keypair.generate(),
+    Keypair.generate(),
+    Keypair.generate(),
+  ];
+  const LARGE_WITHDRAWAL = 500_000_000;
+  const APPROVAL_WINDOW = 60 * 60 * 24;
+  const CONFIG_CHANGE_DELAY = 60 * 60 * 24 * 2;
+
+  const approve = (guardian: Keypair) =>
+    program.methods
+      .approveWithdrawal()
+      .accountsPartial({
+        ...accounts,
+        guardian: guardian.publicKey,
+        user,
+        proposal,
+      })
+      .signers([guardian])
+      .rpc();
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+
+    await program.methods
+      .initialize(new BN(0), new BN(0), null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .deposit(new BN(2_000_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+  });
+
+  it("Sets the first guardians without a delay", async () => {
+    await program.methods
+      .setGuardians(
+        guardians.map((g) => g.publicKey),
+        2,
+        new BN(LARGE_WITHDRAWAL)
+      )
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods.applyGuardians().accountsPartial(accounts).rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.guardians.length, 3);
+    assert.equal(state.guardianThreshold, 2);
+  });
+
+  it("Lets small withdrawals straight through", async () => {
+    await program.methods
+      .withdraw(new BN(LARGE_WITHDRAWAL))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 1_500_000_000);
+  });
+
+  it("Rejects large withdrawals without a proposal", async () => {
+    await expectError(
+      program.methods
+        .withdraw(new BN(LARGE_WITHDRAWAL + 1))
+        .accountsPartial(accounts)
+        .rpc(),
+      "ApprovalRequired"
+    );
+    await expectError(
+      program.methods
+        .close()
+        .accountsPartial(accounts)
+        .rpc(),
+      "ApprovalRequired"
+    );
+  });
+
+  it("Adds up withdrawals that skip approval within a window", async () => {
+    await expectError(
+      program.methods.withdraw(new BN(1)).accountsPartial(accounts).rpc(),
+      "ApprovalRequired"
+    );
+
+    await warpTo(context, (await now(context)) + APPROVAL_WINDOW);
+    await program.methods.withdraw(new BN(1)).accountsPartial(accounts).rpc();
+
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 1_499_999_999);
+  });
+
+  it("Collects guardian approvals on a proposal", async () => {
+    proposal = findProposal(program.programId, vaultState, 0);
+    await program.methods
+      .proposeWithdrawal(new BN(1_000_000_000))
+      .accountsPartial({ ...accounts, proposal })
+      .rpc();
+
+    await approve(guardians[0]);
+    await nextSlot(context);
+    await expectError(approve(guardians[0]), "AlreadyApproved");
+    await expectError(approve(Keypair.generate()), "NotGuardian");
+
+    const state = await program.account.withdrawalProposal.fetch(proposal);
+    assert.equal(state.approvals.length, 1);
+  });
+
+  it("Waits for the threshold before executing", async () => {
+    await expectError(
+      program.methods
+        .executeWithdrawal()
+        .accountsPartial({ ...accounts, proposal })
+        .rpc(),
+      "InsufficientApprovals"
+    );
+
+    await approve(guardians[1]);
+    await nextSlot(context);
+    await program.methods
+      .executeWithdrawal()
+      .accountsPartial({ ...accounts, proposal })
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(proposal));
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 499_999_999);
+  });
+
+  it("Cancels a proposal", async () => {
+    proposal = findProposal(program.programId, vaultState, 1);
+    await program.methods
+      .proposeWithdrawal(new BN(LARGE_WITHDRAWAL))
+      .accountsPartial({ ...accounts, proposal })
+      .rpc();
+    await program.methods
+      .cancelWithdrawal()
+      .accountsPartial({ ...accounts, proposal })
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(proposal));
+  });
+
+  it("Delays changes to an existing guardian set", async () => {
+    await program.methods
+      .setGuardians([], 0, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+
+    await nextSlot(context);
+    await expectError(
+      program.methods.applyGuardians().accountsPartial(accounts).rpc(),
+      "GuardianChangeNotReady"
+    );
+  });
+
+  it("Drops approvals from removed guardians", async () => {
+    proposal = findProposal(program.programId, vaultState, 2);
+    await program.methods
+      .proposeWithdrawal(new BN(LARGE_WITHDRAWAL))
+      .accountsPartial({ ...accounts, proposal })
+      .rpc();
+    await approve(guardians[0]);
+
+    const replacement = Keypair.generate();
+    await program.methods
+      .setGuardians(
+        [guardians[1].publicKey, guardians[2].publicKey, replacement.publicKey],
+        2,
+        new BN(LARGE_WITHDRAWAL)
+      )
+      .accountsPartial(accounts)
+      .rpc();
+    await warpTo(context, (await now(context)) + CONFIG_CHANGE_DELAY);
+    await program.methods.applyGuardians().accountsPartial(accounts).rpc();
+
+    await approve(guardians[1]);
+    const state = await program.account.withdrawalProposal.fetch(proposal);
+    assert.deepEqual(state.approvals.map(String), [
+      guardians[1].publicKey.toBase58(),
+    ]);
+
+    await expectError(
+      program.methods
+        .executeWithdrawal()
+        .accountsPartial({ ...accounts, proposal })
+        .rpc(),
+      "InsufficientApprovals"
+    );
+  });
+});
+
+describe("anchor-vault beneficiary", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+
+  const beneficiary = Keypair.generate();
+  const TIMEOUT = 60 * 60 * 24 * 30;
+  const CONFIG_CHANGE_DELAY = 60 * 60 * 24 * 2;
+
+  const claim = () =>
+    program.methods
+      .claim()
+      .accountsPartial({
+        ...accounts,
+        beneficiary: beneficiary.publicKey,
+        user,
+      })
+      .signers([beneficiary])
+      .rpc();
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+
+    await program.methods
+      .initialize(new BN(0), new BN(0), null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .deposit(new BN(1_000_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+  });
+
+  it("Rejects an inactivity timeout below the minimum", async () => {
+    await expectError(
+      program.methods
+        .setBeneficiary(beneficiary.publicKey, new BN(TIMEOUT - 1))
+        .accountsPartial(accounts)
+        .rpc(),
+      "InvalidInactivityTimeout"
+    );
+  });
+
+  it("Delays naming a beneficiary", async () => {
+    await program.methods
+      .setBeneficiary(beneficiary.publicKey, new BN(TIMEOUT))
+      .accountsPartial(accounts)
+      .rpc();
+
+    await expectError(
+      program.methods.applyBeneficiary().accountsPartial(accounts).rpc(),
+      "BeneficiaryChangeNotReady"
+    );
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.isNull(state.beneficiary);
+  });
+
+  it("Sets a beneficiary after the delay", async () => {
+    await warpTo(context, (await now(context)) + CONFIG_CHANGE_DELAY);
+    await program.methods.applyBeneficiary().accountsPartial(accounts).rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.isTrue(state.beneficiary.equals(beneficiary.publicKey));
+    assert.isNull(state.pendingBeneficiary);
+  });
+
+  it("Resets the timer on heartbeat", async () => {
+    const start = await now(context);
+    await warpTo(context, start + TIMEOUT - 1);
+    await program.methods.heartbeat().accountsPartial(accounts).rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.lastHeartbeat.toNumber(), start + TIMEOUT - 1);
+
+    await warpTo(context, start + TIMEOUT);
+    await expectError(claim(), "OwnerStillActive");
+  });
+
+  it("Rejects claims from anyone else", async () => {
+    const state = await program.account.vaultState.fetch(vaultState);
+    await warpTo(context, state.lastHeartbeat.toNumber() + TIMEOUT);
+
+    const outsider = Keypair.generate();
+    await expectError(
+      program.methods
+        .claim()
+        .accountsPartial({ ...accounts, beneficiary: outsider.publicKey, user })
+        .signers([outsider])
+        .rpc(),
+      "NotBeneficiary"
+    );
+  });
+
+  it("Lets the beneficiary claim after the timeout", async () => {
+    await claim();
+
+    assert.isNull(await context.banksClient.getAccount(vaultState));
+    assert.equal(Number(await context.banksClient.getBalance(vault)), 0);
+
+    const balance = await context.banksClient.getBalance(beneficiary.publicKey);
+    assert.isAbove(Number(balance), 1_000_000_000);
+  });
+});
+
+describe("anchor-vault multiple vaults", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultIndex: PublicKey;
+
+  const vaults = (vaultId: number) =>
+    findVault(program.programId, user, vaultId);
+
+  const initialize = (vaultId: number) =>
+    program.methods
+      .initialize(new BN(vaultId), new BN(0), null, new BN(0))
+      .accountsPartial(vaults(vaultId))
+      .rpc();
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+
+    [vaultIndex] = PublicKey.findProgramAddressSync(
+      [Buffer.from("index"), user.toBuffer()],
+      program.programId
+    );
+  });
+
+  it("Keeps independent vaults per id", async () => {
+    await initialize(1);
+    await initialize(7);
+
+    await program.methods
+      .deposit(new BN(100_000_000))
+      .accountsPartial(vaults(1))
+      .rpc();
+    await program.methods
+      .deposit(new BN(700_000_000))
+      .accountsPartial(vaults(7))
+      .rpc();
+
+    const first = await context.banksClient.getBalance(vaults(1).vault);
+    const second = await context.banksClient.getBalance(vaults(7).vault);
+    assert.equal(Number(first), 100_000_000);
+    assert.equal(Number(second), 700_000_000);
+
+    const state = await program.account.vaultState.fetch(vaults(7).vaultState);
+    assert.equal(state.vaultId.toNumber(), 7);
+  });
+
+  it("Lists the user's vaults in the index", async () => {
+    const index = await program.account.vaultIndex.fetch(vaultIndex);
+    assert.deepEqual(
+      index.vaultIds.map((id) => id.toNumber()),
+      [1, 7]
+    );
+  });
+
+  it("Removes closed vaults from the index", async () => {
+    await program.methods.close().accountsPartial(vaults(1)).rpc();
+
+    const index = await program.account.vaultIndex.fetch(vaultIndex);
+    assert.deepEqual(
+      index.vaultIds.map((id) => id.toNumber()),
+      [7]
+    );
+  });
+
+  it("Migrates a single-vault layout account", async () => {
+    const [legacyState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("state"), user.toBuffer()],
+      program.programId
+    );
+    const [legacyVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), user.toBuffer()],
+      program.programId
+    );
+
+    // The single-vault program stored nothing but its two bumps.
+    const discriminator = IDL.accounts.find(
+      (account) => account.name === "VaultState"
+    ).discriminator;
+    context.setAccount(legacyState, {
+      lamports: 10_000_000,
+      data: Buffer.from([...discriminator, 255, 255]),
+      owner: program.programId,
+      executable: false,
+    });
+    context.setAccount(legacyVault, {
+      lamports: 500_000_000,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    });
+
+    await program.methods
+      .migrateVault(new BN(3))
+      .accountsPartial(vaults(3))
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(legacyState));
+    const balance = await context.banksClient.getBalance(vaults(3).vault);
+    assert.equal(Number(balance), 500_000_000);
+
+    const state = await program.account.vaultState.fetch(vaults(3).vaultState);
+    assert.equal(state.vaultId.toNumber(), 3);
+    assert.equal(state.totalDeposited.toNumber(), 500_000_000);
+
+    const index = await program.account.vaultIndex.fetch(vaultIndex);
+    assert.deepEqual(
+      index.vaultIds.map((id) => id.toNumber()),
+      [7, 3]
+    );
+  });
+});
+
+describe("anchor-vault streams", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+  let start: number;
+
+  const recipient = Keypair.generate();
+
+  const findStream = (id: number) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("stream"), vaultState.toBuffer(), idSeed(id)],
+      program.programId
+    )[0];
+
+  const withdrawStreamed = (stream: PublicKey) =>
+    program.methods
+      .withdrawStreamed()
+      .accountsPartial({
+        recipient: recipient.publicKey,
+        user,
+        vaultState,
+        stream,
+      })
+      .signers([recipient])
+      .rpc();
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+
+    await program.methods
+      .initialize(new BN(0), new BN(0), null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .deposit(new BN(2_000_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+  });
+
+  it("Funds a stream from the vault", async () => {
+    start = (await now(context)) + 100;
+    await program.methods
+      .createStream(
+        recipient.publicKey,
+        new BN(1_000_000_000),
+        new BN(start),
+        new BN(start + 1000),
+        new BN(start + 200)
+      )
+      .accountsPartial({ ...accounts, stream: findStream(0) })
+      .rpc();
+
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 1_000_000_000);
+  });
+
+  it("Pays nothing before the cliff", async () => {
+    await warpTo(context, start + 199);
+    await expectError(withdrawStreamed(findStream(0)), "NothingVested");
+  });
+
+  it("Pays out the linearly vested amount", async () => {
+    await warpTo(context, start + 500);
+    await withdrawStreamed(findStream(0));
+
+    const stream = await program.account.stream.fetch(findStream(0));
+    assert.equal(stream.withdrawn.toNumber(), 500_000_000);
+
+    const balance = await context.banksClient.getBalance(recipient.publicKey);
+    assert.equal(Number(balance), 500_000_000);
+  });
+
+  it("Refuses to close the vault while streams are open", async () => {
+    await expectError(
+      program.methods.close().accountsPartial(accounts).rpc(),
+      "StreamsOutstanding"
+    );
+  });
+
+  it("Splits vested and unvested funds on cancel", async () => {
+    await warpTo(context, start + 750);
+    await program.methods
+      .cancelStream()
+      .accountsPartial({
+        ...accounts,
+        recipient: recipient.publicKey,
+        stream: findStream(0),
+      })
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(findStream(0)));
+
+    const recipientBalance = await context.banksClient.getBalance(
+      recipient.publicKey
+    );
+    assert.equal(Number(recipientBalance), 750_000_000);
+
+    const vaultBalance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(vaultBalance), 1_250_000_000);
+  });
+
+  it("Closes a stream once fully withdrawn", async () => {
+    const streamStart = await now(context);
+    await program.methods
+      .createStream(
+        recipient.publicKey,
+        new BN(100_000_000),
+        new BN(streamStart),
+        new BN(streamStart + 10),
+        new BN(streamStart)
+      )
+      .accountsPartial({ ...accounts, stream: findStream(1) })
+      .rpc();
+
+    await warpTo(context, streamStart + 10);
+    await withdrawStreamed(findStream(1));
+
+    assert.isNull(await context.banksClient.getAccount(findStream(1)));
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.activeStreams, 0);
+  });
+  it("Lets the beneficiary cancel streams once the owner is inactive", async () => {
+    const beneficiary = Keypair.generate();
+    const TIMEOUT = 60 * 60 * 24 * 30;
+    const CONFIG_CHANGE_DELAY = 60 * 60 * 24 * 2;
+
+    const streamStart = await now(context);
+    await program.methods
+      .createStream(
+        recipient.publicKey,
+        new BN(400_000_000),
+        new BN(streamStart),
+        new BN(streamStart + 2 * (CONFIG_CHANGE_DELAY + TIMEOUT)),
+        new BN(streamStart)
+      )
+      .accountsPartial({ ...accounts, stream: findStream(2) })
+      .rpc();
+
+    await program.methods
+      .setBeneficiary(beneficiary.publicKey, new BN(TIMEOUT))
+      .accountsPartial(accounts)
+      .rpc();
+    await warpTo(context, streamStart + CONFIG_CHANGE_DELAY);
+    await program.methods.applyBeneficiary().accountsPartial(accounts).rpc();
+
+    const claimStream = () =>
+      program.methods
+        .claimStream()
+        .accountsPartial({
+          ...accounts,
+          beneficiary: beneficiary.publicKey,
+          user,
+          recipient: recipient.publicKey,
+          stream: findStream(2),
+        })
+        .signers([beneficiary])
+        .rpc();
+
+    await expectError(claimStream(), "OwnerStillActive");
+
+    await warpTo(context, streamStart + CONFIG_CHANGE_DELAY + TIMEOUT);
+    await claimStream();
+
+    assert.isNull(await context.banksClient.getAccount(findStream(2)));
+    const recipientBalance = await context.banksClient.getBalance(
+      recipient.publicKey
+    );
+    assert.equal(Number(recipientBalance), 1_050_000_000);
+    const vaultBalance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(vaultBalance), 950_000_000);
+
+    await program.methods
+      .claim()
+      .accountsPartial({ ...accounts, beneficiary: beneficiary.publicKey, user })
+      .signers([beneficiary])
+      .rpc();
+    assert.equal(Number(await context.banksClient.getBalance(vault)), 0);
+  });
+});
+
+describe("anchor-vault accounting", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+
+  // Sends `tx` straight to the bank and decodes the events from its logs.
+  const sendForEvents = async (tx: Transaction) => {
+    tx.recentBlockhash = context.lastBlockhash;
+    tx.feePayer = context.payer.publicKey;
+    tx.sign(context.payer);
+
+    const meta = await context.banksClient.processTransaction(tx);
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return [...parser.parseLogs(meta.logMessages)];
+  };
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+
+    await program.methods
+      .initialize(new BN(0), new BN(0), null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+  });
+
+  it("Records deposits and emits Deposited", async () => {
+    const tx = await program.methods
+      .deposit(new BN(1_000_000_000))
+      .accountsPartial(accounts)
+      .transaction();
+    const events = await sendForEvents(tx);
+
+    assert.equal(events[0].name, "deposited");
+    assert.equal(events[0].data.amount.toNumber(), 1_000_000_000);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.totalDeposited.toNumber(), 1_000_000_000);
+    assert.isAbove(state.createdAt.toNumber(), 0);
+  });
+
+  it("Records withdrawals and emits Withdrawn", async () => {
+    const tx = await program.methods
+      .withdraw(new BN(300_000_000))
+      .accountsPartial(accounts)
+      .transaction();
+    const events = await sendForEvents(tx);
+
+    assert.equal(events[0].name, "withdrawn");
+    assert.equal(events[0].data.totalWithdrawn.toNumber(), 300_000_000);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.totalWithdrawn.toNumber(), 300_000_000);
+  });
+
+  it("Reports lamports received outside the program", async () => {
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: user,
+          toPubkey: vault,
+          lamports: 50_000_000,
+        })
+      )
+    );
+
+    const report = await program.methods
+      .reconcile()
+      .accountsPartial({ ...accounts, user })
+      .view();
+
+    assert.equal(report.balance.toNumber(), 750_000_000);
+    assert.equal(report.principal.toNumber(), 700_000_000);
+    assert.equal(report.external.toNumber(), 50_000_000);
+  });
+
+  it("Emits Closed with the final balance", async () => {
+    const tx = await program.methods
+      .close()
+      .accountsPartial(accounts)
+      .transaction();
+    const events = await sendForEvents(tx);
+
+    const closed = events.find((event) => event.name === "closed");
+    assert.equal(closed.data.amount.toNumber(), 750_000_000);
+  });
+});
+
+describe("anchor-vault staking", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<AnchorVault>;
+  let user: PublicKey;
+  let vaultState: PublicKey;
+  let vault: PublicKey;
+  let accounts: { vaultState: PublicKey; vault: PublicKey };
+  let stakeAccount: PublicKey;
+
+  const validatorVote = Keypair.generate();
+  const node = Keypair.generate();
+  const STAKE = 2_000_000_000;
+  const REWARDS = 100_000_000;
+
+  before(async () => {
+    context = await startAnchor(".", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<AnchorVault>(IDL as AnchorVault, provider);
+    user = provider.wallet.publicKey;
+    ({ vaultState, vault } = findVault(program.programId, user, 0));
+    accounts = { vaultState, vault };
+
+    [stakeAccount] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), vaultState.toBuffer(), idSeed(0)],
+      program.programId
+    );
+
+    const rent = await context.banksClient.getRent();
+    await provider.sendAndConfirm(
+      VoteProgram.createAccount({
+        fromPubkey: user,
+        votePubkey: validatorVote.publicKey,
+        voteInit: new VoteInit(
+          node.publicKey,
+          node.publicKey,
+          node.publicKey,
+          0
+        ),
+        lamports: Number(rent.minimumBalance(BigInt(VoteProgram.space))),
+      }),
+      [validatorVote, node]
+    );
+
+    await program.methods
+      .initialize(new BN(0), new BN(0), null, new BN(0))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .deposit(new BN(3_000_000_000))
+      .accountsPartial(accounts)
+      .rpc();
+  });
+
+  it("Delegates vault lamports to a validator", async () => {
+    await program.methods
+      .stakeFromVault(new BN(STAKE))
+      .accountsPartial({
+        ...accounts,
+        stakeAccount,
+        validatorVote: validatorVote.publicKey,
+      })
+      .rpc();
+
+    const stake = await context.banksClient.getAccount(stakeAccount);
+    assert.isTrue(stake.owner.equals(anchor.web3.StakeProgram.programId));
+    assert.equal(stake.lamports, STAKE);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.stakeAccounts.length, 1);
+    assert.isTrue(state.stakeAccounts[0].stakeAccount.equals(stakeAccount));
+  });
+
+  it("Counts staked lamports as held by the vault", async () => {
+    const report = await program.methods
+      .reconcile()
+      .accountsPartial({ ...accounts, user })
+      .view();
+
+    assert.equal(report.staked.toNumber(), STAKE);
+    assert.equal(report.external.toNumber(), 0);
+  });
+
+  it("Refuses to close while stake accounts exist", async () => {
+    await expectError(
+      program.methods.close().accountsPartial(accounts).rpc(),
+      "StakeAccountsOutstanding"
+    );
+  });
+
+  it("Deactivates and withdraws the stake back into the vault", async () => {
+    await program.methods
+      .deactivateStake()
+      .accountsPartial({ ...accounts, authority: user, user, stakeAccount })
+      .rpc();
+
+    // Stand in for epoch rewards credited to the stake account.
+    const stake = await context.banksClient.getAccount(stakeAccount);
+    context.setAccount(stakeAccount, {
+      ...stake,
+      lamports: stake.lamports + REWARDS,
+    });
+
+    await program.methods
+      .withdrawStake()
+      .accountsPartial({ ...accounts, authority: user, user, stakeAccount })
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(stakeAccount));
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), 3_000_000_000 + REWARDS);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    assert.equal(state.stakeAccounts.length, 0);
+    assert.equal(state.totalStakeRewards.toNumber(), REWARDS);
+  });
+
+  it("Reconciles after withdrawing more than was deposited", async () => {
+    await program.methods
+      .withdraw(new BN(3_000_000_000 + REWARDS / 2))
+      .accountsPartial(accounts)
+      .rpc();
+
+    const report = await program.methods
+      .reconcile()
+      .accountsPartial({ ...accounts, user })
+      .view();
+
+    assert.equal(report.balance.toNumber(), REWARDS / 2);
+    assert.equal(report.principal.toNumber(), REWARDS / 2);
+    assert.equal(report.external.toNumber(), 0);
+  });
+
+  it("Lets anyone unwind the stake of an inactive owner", async () => {
+    const beneficiary = Keypair.generate();
+    const outsider = Keypair.generate();
+    const TIMEOUT = 60 * 60 * 24 * 30;
+    const CONFIG_CHANGE_DELAY = 60 * 60 * 24 * 2;
+
+    const [secondStake] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), vaultState.toBuffer(), idSeed(1)],
+      program.programId
+    );
+    await program.methods
+      .deposit(new BN(STAKE))
+      .accountsPartial(accounts)
+      .rpc();
+    await program.methods
+      .stakeFromVault(new BN(STAKE))
+      .accountsPartial({
+        ...accounts,
+        stakeAccount: secondStake,
+        validatorVote: validatorVote.publicKey,
+      })
+      .rpc();
+
+    const unwind = () =>
+      program.methods
+        .deactivateStake()
+        .accountsPartial({
+          ...accounts,
+          authority: outsider.publicKey,
+          user,
+          stakeAccount: secondStake,
+        })
+        .signers([outsider])
+        .rpc();
+
+    await expectError(unwind(), "OwnerStillActive");
+
+    await program.methods
+      .setBeneficiary(beneficiary.publicKey, new BN(TIMEOUT))
+      .accountsPartial(accounts)
+      .rpc();
+    const start = await now(context);
+    await warpTo(context, start + CONFIG_CHANGE_DELAY);
+    await program.methods.applyBeneficiary().accountsPartial(accounts).rpc();
+    await warpTo(context, start + CONFIG_CHANGE_DELAY + TIMEOUT);
+
+    await unwind();
+    await program.methods
+      .withdrawStake()
+      .accountsPartial({
+        ...accounts,
+        authority: outsider.publicKey,
+        user,
+        stakeAccount: secondStake,
+      })
+      .signers([outsider])
+      .rpc();
+
+    assert.isNull(await context.banksClient.getAccount(secondStake));
+    const balance = await context.banksClient.getBalance(vault);
+    assert.equal(Number(balance), REWARDS / 2 + STAKE);
+  });
 
-  const program = anchor.workspace.AnchorVault as Program<AnchorVault>;
+  it("Closes once the stake is withdrawn", async () => {
+    await nextSlot(context);
+    await program.methods.close().accountsPartial(accounts).rpc();
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+    assert.isNull(await context.banksClient.getAccount(vaultState));
   });
 });
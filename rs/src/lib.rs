@@ -1,3 +1,3 @@
-mod programs;
+pub mod programs;
 mod prereqs;
 mod cluster1;
\ No newline at end of file
@@ -0,0 +1,268 @@
+use solana_idlgen::idlgen;
+use solana_sdk::{
+    hash::Hash,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    sysvar,
+    transaction::Transaction,
+};
+
+idlgen!({
+    "version": "0.1.0",
+    "name": "escrow",
+    "instructions": [
+        {
+        "name": "make",
+        "accounts": [
+            { "name": "maker", "isMut": true, "isSigner": true },
+            { "name": "mintA", "isMut": false, "isSigner": false },
+            { "name": "mintB", "isMut": false, "isSigner": false },
+            { "name": "makerAtaA", "isMut": true, "isSigner": false },
+            { "name": "escrow", "isMut": true, "isSigner": false },
+            { "name": "vault", "isMut": true, "isSigner": false },
+            { "name": "offerBook", "isMut": true, "isSigner": false },
+            { "name": "associatedTokenProgram", "isMut": false, "isSigner": false },
+            { "name": "tokenProgram", "isMut": false, "isSigner": false },
+            { "name": "systemProgram", "isMut": false, "isSigner": false }
+        ],
+        "args": [
+            { "name": "seed", "type": "u64" },
+            { "name": "deposit", "type": "u64" },
+            { "name": "receive", "type": "u64" },
+            { "name": "expires_at", "type": "i64" },
+            { "name": "taker", "type": { "option": "publicKey" } },
+            { "name": "require_authorization", "type": "bool" }
+        ]
+        },
+        {
+        "name": "take",
+        "accounts": [
+            { "name": "taker", "isMut": true, "isSigner": true },
+            { "name": "maker", "isMut": true, "isSigner": false },
+            { "name": "mintA", "isMut": false, "isSigner": false },
+            { "name": "mintB", "isMut": false, "isSigner": false },
+            { "name": "takerAtaA", "isMut": true, "isSigner": false },
+            { "name": "takerAtaB", "isMut": true, "isSigner": false },
+            { "name": "makerAtaB", "isMut": true, "isSigner": false },
+            { "name": "config", "isMut": false, "isSigner": false },
+            { "name": "treasury", "isMut": false, "isSigner": false },
+            { "name": "treasuryAtaB", "isMut": true, "isSigner": false },
+            { "name": "escrow", "isMut": true, "isSigner": false },
+            { "name": "vault", "isMut": true, "isSigner": false },
+            { "name": "offerBook", "isMut": true, "isSigner": false },
+            { "name": "instructionsSysvar", "isMut": false, "isSigner": false },
+            { "name": "associatedTokenProgram", "isMut": false, "isSigner": false },
+            { "name": "tokenProgram", "isMut": false, "isSigner": false },
+            { "name": "systemProgram", "isMut": false, "isSigner": false }
+        ],
+        "args": [
+            { "name": "expected_version", "type": { "option": "u64" } }
+        ]
+        },
+        {
+        "name": "refund",
+        "accounts": [
+            { "name": "maker", "isMut": true, "isSigner": true },
+            { "name": "mintA", "isMut": false, "isSigner": false },
+            { "name": "makerAtaA", "isMut": true, "isSigner": false },
+            { "name": "escrow", "isMut": true, "isSigner": false },
+            { "name": "vault", "isMut": true, "isSigner": false },
+            { "name": "offerBook", "isMut": true, "isSigner": false },
+            { "name": "associatedTokenProgram", "isMut": false, "isSigner": false },
+            { "name": "tokenProgram", "isMut": false, "isSigner": false },
+            { "name": "systemProgram", "isMut": false, "isSigner": false }
+        ],
+        "args": []
+        }
+    ],
+    "metadata": {
+        "address": "AjgeKU3JYAHkJVScSQdNwF5tyDC86cykdyjnr2Lw4sam"
+    }
+});
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Associated token account of `owner` for `mint` under either token program.
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// One offer, identified by its maker and seed. `token_program` must own
+/// both mints.
+pub struct Offer {
+    pub maker: Pubkey,
+    pub seed: u64,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl Offer {
+    pub fn escrow(&self) -> Pubkey {
+        EscrowProgram::derive_program_address(&[
+            b"escrow",
+            self.maker.as_ref(),
+            self.seed.to_le_bytes().as_ref(),
+        ])
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        associated_token_address(&self.escrow(), &self.mint_a, &self.token_program)
+    }
+
+    pub fn offer_book(&self) -> Pubkey {
+        EscrowProgram::derive_program_address(&[
+            b"book",
+            self.mint_a.as_ref(),
+            self.mint_b.as_ref(),
+        ])
+    }
+
+    pub fn config() -> Pubkey {
+        EscrowProgram::derive_program_address(&[b"config"])
+    }
+
+    /// Opens a public offer of `deposit` `mint_a` for `receive` `mint_b`.
+    pub fn make(
+        &self,
+        maker: &Keypair,
+        deposit: u64,
+        receive: u64,
+        expires_at: i64,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        EscrowProgram::make(
+            &[
+                &maker.pubkey(),
+                &self.mint_a,
+                &self.mint_b,
+                &associated_token_address(&maker.pubkey(), &self.mint_a, &self.token_program),
+                &self.escrow(),
+                &self.vault(),
+                &self.offer_book(),
+                &ASSOCIATED_TOKEN_PROGRAM_ID,
+                &self.token_program,
+                &system_program::id(),
+            ],
+            &MakeArgs {
+                seed: self.seed,
+                deposit,
+                receive,
+                expires_at,
+                taker: None,
+                require_authorization: false,
+            },
+            Some(&maker.pubkey()),
+            &[maker],
+            recent_blockhash,
+        )
+    }
+
+    /// `treasury` is the fee recipient stored in the program config.
+    pub fn take(
+        &self,
+        taker: &Keypair,
+        treasury: &Pubkey,
+        args: &TakeArgs,
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let taker_key = taker.pubkey();
+
+        EscrowProgram::take(
+            &[
+                &taker_key,
+                &self.maker,
+                &self.mint_a,
+                &self.mint_b,
+                &associated_token_address(&taker_key, &self.mint_a, &self.token_program),
+                &associated_token_address(&taker_key, &self.mint_b, &self.token_program),
+                &associated_token_address(&self.maker, &self.mint_b, &self.token_program),
+                &Self::config(),
+                treasury,
+                &associated_token_address(treasury, &self.mint_b, &self.token_program),
+                &self.escrow(),
+                &self.vault(),
+                &self.offer_book(),
+                &sysvar::instructions::id(),
+                &ASSOCIATED_TOKEN_PROGRAM_ID,
+                &self.token_program,
+                &system_program::id(),
+            ],
+            args,
+            Some(&taker_key),
+            &[taker],
+            recent_blockhash,
+        )
+    }
+
+    pub fn refund(&self, maker: &Keypair, recent_blockhash: Hash) -> Transaction {
+        EscrowProgram::refund(
+            &[
+                &maker.pubkey(),
+                &self.mint_a,
+                &associated_token_address(&maker.pubkey(), &self.mint_a, &self.token_program),
+                &self.escrow(),
+                &self.vault(),
+                &self.offer_book(),
+                &ASSOCIATED_TOKEN_PROGRAM_ID,
+                &self.token_program,
+                &system_program::id(),
+            ],
+            &RefundArgs {},
+            Some(&maker.pubkey()),
+            &[maker],
+            recent_blockhash,
+        )
+    }
+}
+
+#[cfg(test)]
+mod escrow_tests {
+    use super::*;
+
+    fn offer(maker: &Keypair) -> Offer {
+        Offer {
+            maker: maker.pubkey(),
+            seed: 7,
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM_ID,
+        }
+    }
+
+    #[test]
+    fn make_encodes_the_offer_seed() {
+        let maker = Keypair::new();
+        let offer = offer(&maker);
+        let tx = offer.make(&maker, 100, 200, 0, Hash::default());
+
+        assert_eq!(tx.message.instructions[0].data[8..16], 7u64.to_le_bytes());
+        assert!(tx.message.account_keys.contains(&offer.escrow()));
+        assert!(tx.message.account_keys.contains(&offer.offer_book()));
+    }
+
+    #[test]
+    fn take_and_refund_pass_the_offer_book() {
+        let (maker, taker) = (Keypair::new(), Keypair::new());
+        let offer = offer(&maker);
+        let take = offer.take(
+            &taker,
+            &Pubkey::new_unique(),
+            &TakeArgs { expected_version: None },
+            Hash::default(),
+        );
+        let refund = offer.refund(&maker, Hash::default());
+
+        assert!(take.message.account_keys.contains(&offer.offer_book()));
+        assert!(refund.message.account_keys.contains(&offer.offer_book()));
+    }
+}
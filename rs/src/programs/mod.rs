@@ -1,2 +1,3 @@
 // Programs
-pub mod wba_prereq;
\ No newline at end of file
+pub mod wba_prereq;
+pub mod escrow;
\ No newline at end of file